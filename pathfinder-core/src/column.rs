@@ -0,0 +1,141 @@
+use crate::entry::{Entry, EntryType};
+use crate::file_manager::FileManager;
+use crate::sorting::SortKey;
+use crate::util;
+use ratatui::layout::Constraint;
+
+/// Columns that can be shown in the explorer table.
+/// The name column is always shown and takes the remaining space.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Column {
+    Name,
+    Size,
+    Modified,
+    Changed,
+    Permissions,
+    Owner,
+    Group,
+    Links,
+    Inode,
+    Type,
+}
+
+impl Column {
+    /// all columns in the order they are laid out
+    pub const ALL: [Column; 10] = [
+        Column::Name,
+        Column::Size,
+        Column::Modified,
+        Column::Changed,
+        Column::Permissions,
+        Column::Owner,
+        Column::Group,
+        Column::Links,
+        Column::Inode,
+        Column::Type,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Name => "FILENAME",
+            Column::Size => "SIZE",
+            Column::Modified => "MODIFIED",
+            Column::Changed => "CHANGED",
+            Column::Permissions => "PERMISSIONS",
+            Column::Owner => "OWNER",
+            Column::Group => "GROUP",
+            Column::Links => "LINKS",
+            Column::Inode => "INODE",
+            Column::Type => "TYPE",
+        }
+    }
+
+    /// the name used in config files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Name => "name",
+            Column::Size => "size",
+            Column::Modified => "modified",
+            Column::Changed => "changed",
+            Column::Permissions => "permissions",
+            Column::Owner => "owner",
+            Column::Group => "group",
+            Column::Links => "links",
+            Column::Inode => "inode",
+            Column::Type => "type",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Column> {
+        Column::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// the sort key used when the header of this column is clicked
+    pub fn sort_key(&self) -> Option<SortKey> {
+        match self {
+            Column::Name => Some(SortKey::Name),
+            Column::Size => Some(SortKey::Size),
+            Column::Modified => Some(SortKey::Modified),
+            Column::Changed => Some(SortKey::Changed),
+            Column::Type => Some(SortKey::Type),
+            _ => None,
+        }
+    }
+
+    pub fn width(&self) -> Constraint {
+        match self {
+            Column::Name => Constraint::Fill(1),
+            Column::Size => Constraint::Length(12),
+            Column::Modified | Column::Changed => Constraint::Length(16),
+            Column::Permissions => Constraint::Length(11),
+            Column::Owner | Column::Group => Constraint::Length(10),
+            Column::Links => Constraint::Length(5),
+            Column::Inode => Constraint::Length(10),
+            Column::Type => Constraint::Length(6),
+        }
+    }
+
+    /// the text of this column for the given entry
+    pub fn cell(&self, entry: &Entry, file_manager: &FileManager) -> String {
+        match self {
+            Column::Name => match &entry.symlink_target {
+                Some(target) => format!(
+                    "{} -> {}",
+                    entry.name.to_string_lossy(),
+                    target.to_string_lossy()
+                ),
+                None => entry.name.to_string_lossy().into_owned(),
+            },
+            Column::Size => match entry.file_type {
+                EntryType::Dir => match file_manager.dir_size(&entry.path) {
+                    Some(size) => util::format_size(size, file_manager.size_format),
+                    None if file_manager.is_dir_size_pending(&entry.path) => "…".to_owned(),
+                    None => String::new(),
+                },
+                EntryType::File => util::format_size(entry.size, file_manager.size_format),
+                _ => String::new(),
+            },
+            Column::Type => entry.file_type.label().to_owned(),
+            _ => Column::unix_cell(self, entry),
+        }
+    }
+
+    #[cfg(unix)]
+    fn unix_cell(column: &Column, entry: &Entry) -> String {
+        match column {
+            Column::Modified => entry.modified.map(util::format_time).unwrap_or_default(),
+            Column::Changed => entry.changed.map(util::format_time).unwrap_or_default(),
+            Column::Permissions => util::format_permissions(entry.mode),
+            Column::Owner => util::user_name(entry.uid),
+            Column::Group => util::group_name(entry.gid),
+            Column::Links => entry.nlink.to_string(),
+            Column::Inode => entry.ino.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn unix_cell(_column: &Column, _entry: &Entry) -> String {
+        String::new()
+    }
+}
@@ -0,0 +1,323 @@
+use crate::error::{self, Context, Operation};
+use crate::keymap::Action;
+use crate::sorting::{SortKey, Sorting};
+use std::fs;
+use std::path::PathBuf;
+
+/// the commands of the `:` command line besides the action names
+pub const COMMAND_NAMES: [&str; 10] = [
+    "cd",
+    "mkdir",
+    "touch",
+    "sort",
+    "set",
+    "select",
+    "rename",
+    "open_with",
+    "run",
+    "quit",
+];
+
+/// the options `:set` understands
+const SET_OPTIONS: [&str; 3] = ["hidden", "nohidden", "hidden!"];
+
+/// at most this many lines are kept in the history file
+const MAX_HISTORY: usize = 500;
+
+/// A command typed into the `:` command line.
+/// Arguments are split like a shell does, so names with spaces can be quoted or escaped
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Mkdir(Vec<PathBuf>),
+    Touch(Vec<PathBuf>),
+    Sort(Sorting),
+    Cd(PathBuf),
+    /// show hidden files, None toggles
+    SetHidden(Option<bool>),
+    /// adds the entries matching any of the shell patterns to the selection
+    Select(Vec<String>),
+    /// renames the first path, or the entry under the cursor if it is missing
+    Rename(Option<PathBuf>, PathBuf),
+    /// opens the entry under the cursor with a command line like `gimp %f` or `vim -p %F`,
+    /// `%f` is the entry and `%F` the selection
+    OpenWith {
+        command: String,
+        terminal: bool,
+    },
+    /// a shell command like `:!du -sh %s`, `%s` is the selection and `%d` the current folder.
+    /// Attached commands get the terminal, the output of the others is shown in a popup
+    Shell {
+        command: String,
+        attached: bool,
+    },
+    /// a custom action of config.toml by its name
+    Run(String),
+    /// an action by its name in keys.toml, e.g. `:toggle_hidden`
+    Action(Action),
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, String> {
+        //like in vim, `:!` runs the rest of the line in the terminal
+        if let Some(command) = line.trim_start().strip_prefix('!') {
+            return match command.trim() {
+                "" => Err("! needs a shell command".to_owned()),
+                command => Ok(Command::Shell {
+                    command: command.to_owned(),
+                    attached: true,
+                }),
+            };
+        }
+        let (words, _) = split_words(line)?;
+        //the command of open_with is split later, when the placeholders are replaced
+        if let [(_, name), (start, _), ..] = words.as_slice()
+            && name == "open_with"
+        {
+            return Ok(Command::OpenWith {
+                command: line[*start..].to_owned(),
+                terminal: true,
+            });
+        }
+        let words: Vec<String> = words.into_iter().map(|(_, word)| word).collect();
+        let (name, args) = match words.split_first() {
+            Some((name, args)) => (name.as_str(), args),
+            None => return Err("empty command".to_owned()),
+        };
+        match name {
+            "mkdir" => Ok(Command::Mkdir(paths(name, args)?)),
+            "touch" => Ok(Command::Touch(paths(name, args)?)),
+            "sort" => Sorting::parse(&args.join(" ")).map(Command::Sort),
+            "cd" => match args {
+                //like the shell, a plain `cd` goes home
+                [] => Ok(Command::Cd(expand_tilde("~"))),
+                [path] => Ok(Command::Cd(expand_tilde(path))),
+                _ => Err("cd takes one directory".to_owned()),
+            },
+            "set" => match args {
+                [option] => match option.as_str() {
+                    "hidden" => Ok(Command::SetHidden(Some(true))),
+                    "nohidden" => Ok(Command::SetHidden(Some(false))),
+                    "hidden!" | "invhidden" => Ok(Command::SetHidden(None)),
+                    _ => Err(format!("unknown option \"{}\"", option)),
+                },
+                _ => Err(format!("set takes one of {}", SET_OPTIONS.join(", "))),
+            },
+            "select" if args.is_empty() => Err("select needs a pattern like *.log".to_owned()),
+            "select" => Ok(Command::Select(args.to_vec())),
+            "rename" => match args {
+                [new] => Ok(Command::Rename(None, expand_tilde(new))),
+                [old, new] => Ok(Command::Rename(Some(expand_tilde(old)), expand_tilde(new))),
+                _ => Err("rename takes a new name or an old and a new name".to_owned()),
+            },
+            "run" => match args {
+                [name] => Ok(Command::Run(name.clone())),
+                _ => Err("run takes the name of a custom action".to_owned()),
+            },
+            "q" | "quit" => Ok(Command::Action(Action::Quit)),
+            _ => match Action::from_name(&words.join(" ")) {
+                Some(action) => Ok(Command::Action(action)),
+                None => Err(format!("unknown command \"{}\"", name)),
+            },
+        }
+    }
+}
+
+fn paths(name: &str, args: &[String]) -> Result<Vec<PathBuf>, String> {
+    if args.is_empty() {
+        return Err(format!("{} needs at least one path", name));
+    }
+    Ok(args.iter().map(|arg| expand_tilde(arg)).collect())
+}
+
+/// replaces a leading `~` with the home directory
+fn expand_tilde(path: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match (path, home) {
+        ("~", Some(home)) => home,
+        (path, Some(home)) if path.starts_with("~/") => home.join(&path[2..]),
+        (path, _) => PathBuf::from(path),
+    }
+}
+
+/// splits line into words like a shell, with the byte index each word starts at.
+/// Single and double quotes group words and a backslash escapes the next character.
+/// The flag tells if line ends inside of a word
+pub fn split_words(line: &str) -> Result<(Vec<(usize, String)>, bool), String> {
+    let mut words = Vec::new();
+    //start and text of the word being read
+    let mut word: Option<(usize, String)> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if quote.is_none() && c.is_whitespace() {
+            words.extend(word.take());
+            continue;
+        }
+        let text = &mut word.get_or_insert_with(|| (index, String::new())).1;
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (Some('\''), c) => text.push(c),
+            (_, '\\') => match chars.next() {
+                Some((_, escaped)) => text.push(escaped),
+                None => return Err("nothing to escape after \\".to_owned()),
+            },
+            (_, c) => text.push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("unterminated quote".to_owned());
+    }
+    let in_word = word.is_some();
+    words.extend(word);
+    Ok((words, in_word))
+}
+
+/// escapes the characters split_words treats specially
+fn escape(word: &str) -> String {
+    let mut escaped = String::new();
+    for c in word.chars() {
+        if c.is_whitespace() || matches!(c, '\\' | '\'' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Completions of the last word of line.
+/// Returns the byte index the word starts at and what it can be replaced with
+pub fn complete(line: &str) -> (usize, Vec<String>) {
+    let (mut words, in_word) = match split_words(line) {
+        Ok(split) => split,
+        Err(_e) => return (line.len(), Vec::new()),
+    };
+    //after a space a new, empty word is completed
+    if !in_word {
+        words.push((line.len(), String::new()));
+    }
+    let (start, word) = words.last().cloned().unwrap_or_default();
+    let candidates = match (words.len(), words[0].1.as_str()) {
+        (1, _) => {
+            let mut names: Vec<String> = COMMAND_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .chain(Action::ALL.iter().map(|action| action.name()))
+                .filter(|name| name.starts_with(&word))
+                .collect();
+            names.sort();
+            names.dedup();
+            names
+        }
+        (2, "sort") => SortKey::ALL
+            .iter()
+            .map(|key| key.name())
+            .chain(["none"])
+            .filter(|name| name.starts_with(&word))
+            .map(str::to_owned)
+            .collect(),
+        (3, "sort") => ["asc", "desc"]
+            .iter()
+            .filter(|order| order.starts_with(&word))
+            .map(|order| order.to_string())
+            .collect(),
+        (2, "set") => SET_OPTIONS
+            .iter()
+            .filter(|option| option.starts_with(&word))
+            .map(|option| option.to_string())
+            .collect(),
+        (_, "cd") | (_, "mkdir") => complete_path(&word, true),
+        (_, "touch") | (_, "rename") | (_, "select") => complete_path(&word, false),
+        _ => Vec::new(),
+    };
+    (start, candidates.iter().map(|c| escape(c)).collect())
+}
+
+/// paths starting with prefix, directories end with a slash.
+/// Hidden entries are only offered once the name starts with a dot
+fn complete_path(prefix: &str, dirs_only: bool) -> Vec<String> {
+    let (dir, name) = match prefix.rfind('/') {
+        Some(index) => prefix.split_at(index + 1),
+        None => ("", prefix),
+    };
+    let read_dir = if dir.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_tilde(dir)
+    };
+    let entries = match fs::read_dir(read_dir) {
+        Ok(entries) => entries,
+        Err(_e) => return Vec::new(),
+    };
+    let mut candidates: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let entry_name = entry.file_name().to_str()?.to_owned();
+            if !entry_name.starts_with(name)
+                || entry_name.starts_with('.') && !name.starts_with('.')
+            {
+                return None;
+            }
+            //follows symlinks, so links to directories complete like directories
+            let is_dir = entry.path().is_dir();
+            match (is_dir, dirs_only) {
+                (true, _) => Some(format!("{}{}/", dir, entry_name)),
+                (false, false) => Some(format!("{}{}", dir, entry_name)),
+                (false, true) => None,
+            }
+        })
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// the lines entered into the command line, oldest first
+pub struct CommandHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    /// reads the history file in the state directory, a missing file gives an empty history
+    pub fn load() -> CommandHistory {
+        CommandHistory::load_from(crate::util::state_dir().map(|dir| dir.join("history")))
+    }
+
+    pub fn load_from(path: Option<PathBuf>) -> CommandHistory {
+        //losing the history is not worth an error popup, an unreadable file starts a new one
+        let entries = match path.as_ref().map(fs::read_to_string) {
+            Some(Ok(text)) => text.lines().map(str::to_owned).collect(),
+            _ => Vec::new(),
+        };
+        CommandHistory { entries, path }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// adds line as the newest entry, an older copy of it is removed
+    pub fn push(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != line);
+        self.entries.push(line.to_owned());
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY);
+        }
+    }
+
+    pub fn save(&self) -> error::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(Operation::SaveHistory, path)?;
+        }
+        let mut text = self.entries.join("\n");
+        text.push('\n');
+        fs::write(path, text).context(Operation::SaveHistory, path)
+    }
+}
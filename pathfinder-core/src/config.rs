@@ -0,0 +1,189 @@
+use crate::column::Column;
+use crate::file_manager::SortDir;
+use crate::sorting::Sorting;
+use serde::Deserialize;
+use serde::de::{self, Deserializer};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+/// Settings of config.toml in the config directory.
+/// Every setting is optional, missing ones keep their default
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// e.g. `"name"`, `"mtime desc"` or `"none"`
+    #[serde(deserialize_with = "deserialize_sorting")]
+    pub sorting: Sorting,
+    /// `"start"`, `"end"` or `"none"`
+    #[serde(deserialize_with = "deserialize_dir_sorting")]
+    pub dir_sorting: SortDir,
+    pub show_hidden: bool,
+    /// the name column is always shown, e.g. `["name", "size", "modified"]`
+    #[serde(deserialize_with = "deserialize_columns")]
+    pub columns: Vec<Column>,
+    /// `"dark"` or `"light"`
+    pub theme: String,
+    /// overrides styles of the theme, e.g. `directory = "bold blue"`
+    pub styles: HashMap<String, String>,
+    /// color entries like `ls` does if LS_COLORS is set
+    pub ls_colors: bool,
+    pub preview: PreviewConfig,
+    pub confirm: ConfirmConfig,
+    /// opens text files, `$VISUAL` or `$EDITOR` if it is missing
+    pub editor: Option<String>,
+    /// opens files no rule and no editor is for, `xdg-open` if it is missing
+    pub opener: Option<String>,
+    /// which program opens which files, the first matching rule is used
+    pub open: Vec<OpenRule>,
+    /// shell commands that can be bound to keys and run with `:run name`
+    pub actions: Vec<CustomAction>,
+}
+
+/// e.g. `{ name = "archive", command = "tar czf archive.tgz %s", keys = "g a" }`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomAction {
+    pub name: String,
+    /// a shell command, `%s` is replaced by the selection and `%d` by the current folder
+    pub command: String,
+    /// run in the terminal instead of showing the output in a popup
+    #[serde(default)]
+    pub attached: bool,
+    /// a key sequence like in keys.toml
+    pub keys: Option<String>,
+}
+
+/// e.g. `{ mime = "image/*", command = "feh %f" }`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenRule {
+    /// extensions without the dot, matched case insensitive
+    #[serde(default)]
+    pub ext: Vec<String>,
+    /// a pattern like `video/*`
+    pub mime: Option<String>,
+    /// `%f` is replaced by the file, without it the file is appended
+    pub command: String,
+    /// the program runs in the terminal instead of next to the explorer
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreviewConfig {
+    /// show a preview of the entry under the cursor next to the table
+    pub enabled: bool,
+    /// files are only read up to this size
+    pub max_bytes: u64,
+}
+
+/// which actions ask before they are carried out
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+    pub delete: bool,
+    /// pasting over existing files
+    pub overwrite: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            sorting: Sorting::Unsorted,
+            dir_sorting: SortDir::Unsorted,
+            show_hidden: false,
+            columns: vec![Column::Name, Column::Size],
+            theme: "dark".to_owned(),
+            styles: HashMap::new(),
+            ls_colors: true,
+            preview: PreviewConfig::default(),
+            confirm: ConfirmConfig::default(),
+            editor: None,
+            opener: None,
+            open: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl Default for PreviewConfig {
+    fn default() -> PreviewConfig {
+        PreviewConfig {
+            enabled: false,
+            max_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> ConfirmConfig {
+        ConfirmConfig {
+            delete: true,
+            overwrite: true,
+        }
+    }
+}
+
+impl Config {
+    /// reads config.toml, a missing file gives the default config
+    pub fn load() -> Result<Config, String> {
+        let path = match crate::util::config_dir() {
+            Some(dir) => dir.join("config.toml"),
+            None => return Ok(Config::default()),
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        Config::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Config, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+fn deserialize_sorting<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Sorting, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Sorting::parse(&text).map_err(de::Error::custom)
+}
+
+fn deserialize_dir_sorting<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SortDir, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        "start" => Ok(SortDir::Start),
+        "end" => Ok(SortDir::End),
+        "none" => Ok(SortDir::Unsorted),
+        other => Err(de::Error::custom(format!(
+            "unknown dir_sorting \"{}\", expected \"start\", \"end\" or \"none\"",
+            other
+        ))),
+    }
+}
+
+fn deserialize_columns<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Column>, D::Error> {
+    let mut columns = vec![Column::Name];
+    let mut listed = Vec::new();
+    for name in Vec::<String>::deserialize(deserializer)? {
+        let column = Column::from_name(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown column \"{}\"", name)))?;
+        if listed.contains(&column) {
+            return Err(de::Error::custom(format!(
+                "column \"{}\" is listed twice",
+                name
+            )));
+        }
+        listed.push(column);
+        //the name column is always there, as the first one
+        if column != Column::Name {
+            columns.push(column);
+        }
+    }
+    Ok(columns)
+}
@@ -0,0 +1,57 @@
+use crate::entry::Entry;
+use crate::event_loop::WorkerMessage;
+use std::fs::ReadDir;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// entries are sent in batches, so the receiver doesn't have to handle every single one
+const BATCH_SIZE: usize = 512;
+/// a batch is sent early if reading the directory is slow
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+pub enum DirLoadEvent {
+    /// a batch of entries of the load with the given generation
+    Entries(u64, Vec<Entry>),
+    /// the load with the given generation read all entries
+    Done(u64),
+}
+
+/// reads the already opened directory dir on a background thread.
+/// Reading stops as soon as cancel is set
+pub fn spawn_dir_loader(
+    entry_iter: ReadDir,
+    dir: PathBuf,
+    generation: u64,
+    cancel: Arc<AtomicBool>,
+    sender: Sender<WorkerMessage>,
+) {
+    thread::spawn(move || {
+        let mut batch = Vec::new();
+        let mut last_send = Instant::now();
+        //entries that can't even be named are skipped
+        for dir_entry in entry_iter.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            batch.push(Entry::from_dir_entry(&dir_entry, &dir));
+            if batch.len() >= BATCH_SIZE || last_send.elapsed() >= BATCH_INTERVAL {
+                let entries = std::mem::take(&mut batch);
+                let message = WorkerMessage::DirLoad(DirLoadEvent::Entries(generation, entries));
+                if sender.send(message).is_err() {
+                    return;
+                }
+                last_send = Instant::now();
+            }
+        }
+        if !batch.is_empty() {
+            let _ = sender.send(WorkerMessage::DirLoad(DirLoadEvent::Entries(
+                generation, batch,
+            )));
+        }
+        let _ = sender.send(WorkerMessage::DirLoad(DirLoadEvent::Done(generation)));
+    });
+}
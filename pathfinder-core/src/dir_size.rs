@@ -0,0 +1,120 @@
+use crate::event_loop::WorkerMessage;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// calculates the recursive sizes of the given directories on a background thread.
+/// Every result is sent as soon as its directory is finished, tagged with generation
+pub fn spawn_dir_size_worker(paths: Vec<PathBuf>, generation: u64, sender: Sender<WorkerMessage>) {
+    thread::spawn(move || {
+        for path in paths {
+            let size = dir_size(&path);
+            let message = WorkerMessage::DirSize(generation, path, size);
+            if sender.send(message).is_err() {
+                //the FileManager is gone, nobody is interested in the result anymore
+                return;
+            }
+        }
+    });
+}
+
+/// sums up the apparent sizes of all files below path, like `du --apparent-size`,
+/// so folders and files in the SIZE column compare fairly.
+/// Symlinks are not followed, hard links count once and unreadable entries are skipped
+pub fn dir_size(path: &Path) -> u64 {
+    Walk::new(None).sum(path)
+}
+
+/// State shared by the threads walking one tree, for dir_size and the disk usage scan
+pub(crate) struct Walk {
+    /// the device not to leave, None to cross filesystems
+    pub device: Option<u64>,
+    /// how many more threads may be started
+    idle: AtomicUsize,
+    /// (device, inode) of the files with several hard links which were counted already
+    seen: Mutex<HashSet<(u64, u64)>>,
+}
+
+impl Walk {
+    pub fn new(device: Option<u64>) -> Walk {
+        let workers = thread::available_parallelism().map_or(4, |n| n.get());
+        Walk {
+            device,
+            idle: AtomicUsize::new(workers - 1),
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn sum(&self, dir: &Path) -> u64 {
+        let entry_iter = match fs::read_dir(dir) {
+            Ok(entry_iter) => entry_iter,
+            Err(_e) => return 0,
+        };
+        let mut size = 0;
+        let mut dirs = Vec::new();
+        for entry in entry_iter.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_e) => continue,
+            };
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                size += self.file_size(&metadata);
+            }
+        }
+        size + self
+            .each_dir(dirs, &|dir| self.sum(dir))
+            .iter()
+            .sum::<u64>()
+    }
+
+    /// the apparent size of a file, 0 for further hard links to a file counted before
+    pub fn file_size(&self, metadata: &fs::Metadata) -> u64 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if metadata.nlink() > 1 {
+                let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+                if !seen.insert((metadata.dev(), metadata.ino())) {
+                    return 0;
+                }
+            }
+        }
+        metadata.len()
+    }
+
+    /// calls f for every directory, on new threads as long as there are idle cores,
+    /// so every level of the tree is walked in parallel and not only the top one
+    pub fn each_dir<T: Send>(&self, dirs: Vec<PathBuf>, f: &(dyn Fn(&Path) -> T + Sync)) -> Vec<T> {
+        thread::scope(|s| {
+            let mut results = Vec::new();
+            let mut handles = Vec::new();
+            for dir in dirs {
+                if self.take_thread() {
+                    handles.push(s.spawn(move || {
+                        let result = f(&dir);
+                        self.idle.fetch_add(1, Ordering::Relaxed);
+                        result
+                    }));
+                } else {
+                    results.push(f(&dir));
+                }
+            }
+            results.extend(handles.into_iter().filter_map(|handle| handle.join().ok()));
+            results
+        })
+    }
+
+    fn take_thread(&self) -> bool {
+        self.idle
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |idle| {
+                idle.checked_sub(1)
+            })
+            .is_ok()
+    }
+}
@@ -0,0 +1,133 @@
+use crate::dir_size::Walk;
+use crate::event_loop::WorkerMessage;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// A node of a disk usage scan. Sizes of directories are the sum of everything below them
+pub struct DuNode {
+    pub name: OsString,
+    pub size: u64,
+    pub is_dir: bool,
+    /// sorted by size, biggest first
+    pub children: Vec<DuNode>,
+}
+
+impl DuNode {
+    /// get the node at the given path of names below this node
+    pub fn get(&self, path: &[OsString]) -> Option<&DuNode> {
+        let mut node = self;
+        for name in path {
+            node = node.children.iter().find(|c| &c.name == name)?;
+        }
+        Some(node)
+    }
+
+    /// removes the node at the given path and subtracts its size from all parents.
+    /// Returns the removed size
+    pub fn remove(&mut self, path: &[OsString]) -> Option<u64> {
+        let (name, rest) = path.split_first()?;
+        let index = self.children.iter().position(|c| &c.name == name)?;
+        let removed = if rest.is_empty() {
+            self.children.remove(index).size
+        } else {
+            self.children[index].remove(rest)?
+        };
+        self.size -= removed;
+        self.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+        Some(removed)
+    }
+
+    /// calls f with the full path and size of every directory in this tree
+    pub fn for_each_dir(&self, path: &Path, f: &mut impl FnMut(PathBuf, u64)) {
+        if !self.is_dir {
+            return;
+        }
+        f(path.to_path_buf(), self.size);
+        for child in &self.children {
+            child.for_each_dir(&path.join(&child.name), f);
+        }
+    }
+}
+
+/// scans root on a background thread and sends the finished tree, tagged with generation
+pub fn spawn_scan(root: PathBuf, generation: u64, sender: Sender<WorkerMessage>) {
+    thread::spawn(move || {
+        let node = scan(&root);
+        let _ = sender.send(WorkerMessage::DiskUsage(generation, root, node));
+    });
+}
+
+/// scans the tree below root without crossing filesystem boundaries, in parallel.
+/// Sizes are apparent sizes and hard links count once, as for dir_size
+pub fn scan(root: &Path) -> DuNode {
+    let walk = Walk::new(device_of(root));
+    scan_dir(root, &walk).unwrap_or_else(|| DuNode {
+        name: root.file_name().map(|n| n.to_owned()).unwrap_or_default(),
+        size: 0,
+        is_dir: true,
+        children: Vec::new(),
+    })
+}
+
+/// recursively scans a single directory, returns None if it is on another filesystem
+fn scan_dir(dir: &Path, walk: &Walk) -> Option<DuNode> {
+    if walk.device.is_some() && device_of(dir) != walk.device {
+        return None;
+    }
+    let mut children = Vec::new();
+    let mut dirs = Vec::new();
+    if let Ok(entry_iter) = fs::read_dir(dir) {
+        for entry in entry_iter.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_e) => continue,
+            };
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                children.push(DuNode {
+                    name: entry.file_name(),
+                    size: walk.file_size(&metadata),
+                    is_dir: false,
+                    children: Vec::new(),
+                });
+            }
+        }
+    }
+    children.extend(
+        walk.each_dir(dirs, &|dir| scan_dir(dir, walk))
+            .into_iter()
+            .flatten(),
+    );
+    children.sort_by_key(|c| std::cmp::Reverse(c.size));
+    Some(DuNode {
+        name: dir.file_name().map(|n| n.to_owned()).unwrap_or_default(),
+        size: children.iter().map(|c| c.size).sum(),
+        is_dir: true,
+        children,
+    })
+}
+
+#[cfg(unix)]
+fn device_of(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::symlink_metadata(path).ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// collects the sizes of all directories of a scan, keyed by their full path
+pub fn dir_sizes(root: &Path, node: &DuNode) -> HashMap<PathBuf, u64> {
+    let mut sizes = HashMap::new();
+    node.for_each_dir(root, &mut |path, size| {
+        sizes.insert(path, size);
+    });
+    sizes
+}
@@ -0,0 +1,158 @@
+use std::ffi::OsString;
+use std::fs::{self, DirEntry, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum EntryType {
+    Dir,
+    File,
+    Symlink,
+    Fifo,
+    Socket,
+    CharDevice,
+    BlockDevice,
+    Other,
+}
+
+impl EntryType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryType::Dir => "dir",
+            EntryType::File => "file",
+            EntryType::Symlink => "link",
+            EntryType::Fifo => "fifo",
+            EntryType::Socket => "socket",
+            EntryType::CharDevice => "char",
+            EntryType::BlockDevice => "block",
+            EntryType::Other => "other",
+        }
+    }
+
+    #[cfg(unix)]
+    fn from_file_type(file_type: fs::FileType) -> EntryType {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_dir() {
+            EntryType::Dir
+        } else if file_type.is_symlink() {
+            EntryType::Symlink
+        } else if file_type.is_file() {
+            EntryType::File
+        } else if file_type.is_fifo() {
+            EntryType::Fifo
+        } else if file_type.is_socket() {
+            EntryType::Socket
+        } else if file_type.is_char_device() {
+            EntryType::CharDevice
+        } else if file_type.is_block_device() {
+            EntryType::BlockDevice
+        } else {
+            EntryType::Other
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn from_file_type(file_type: fs::FileType) -> EntryType {
+        if file_type.is_dir() {
+            EntryType::Dir
+        } else if file_type.is_symlink() {
+            EntryType::Symlink
+        } else if file_type.is_file() {
+            EntryType::File
+        } else {
+            EntryType::Other
+        }
+    }
+}
+
+/// A directory entry with everything the explorer needs to sort and draw it.
+/// It is built once when the directory is read, so neither sorting nor drawing
+/// has to touch the filesystem again
+pub struct Entry {
+    pub name: OsString,
+    /// absolute path of the entry
+    pub path: PathBuf,
+    pub file_type: EntryType,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// status change time (ctime) on unix, creation time elsewhere
+    pub changed: Option<SystemTime>,
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub nlink: u64,
+    pub ino: u64,
+    pub symlink_target: Option<PathBuf>,
+    /// a symlink whose target doesn't exist
+    pub broken_link: bool,
+    /// set if the metadata of the entry couldn't be read
+    pub error: Option<String>,
+}
+
+impl Entry {
+    /// reads the metadata of a DirEntry of the directory dir.
+    /// Symlinks are not followed
+    pub fn from_dir_entry(dir_entry: &DirEntry, dir: &Path) -> Entry {
+        let name = dir_entry.file_name();
+        let path = dir.join(&name);
+        let mut entry = Entry {
+            name,
+            path,
+            file_type: EntryType::Other,
+            size: 0,
+            modified: None,
+            changed: None,
+            mode: 0,
+            uid: 0,
+            gid: 0,
+            nlink: 0,
+            ino: 0,
+            symlink_target: None,
+            broken_link: false,
+            error: None,
+        };
+        match dir_entry.metadata() {
+            Ok(metadata) => entry.fill_metadata(&metadata),
+            Err(e) => {
+                if let Ok(file_type) = dir_entry.file_type() {
+                    entry.file_type = EntryType::from_file_type(file_type);
+                }
+                entry.error = Some(e.to_string());
+            }
+        }
+        if entry.file_type == EntryType::Symlink {
+            entry.symlink_target = fs::read_link(&entry.path).ok();
+            entry.broken_link = entry.path.metadata().is_err();
+        }
+        entry
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type == EntryType::Dir
+    }
+
+    #[cfg(unix)]
+    fn fill_metadata(&mut self, metadata: &Metadata) {
+        use std::os::unix::fs::MetadataExt;
+        use std::time::Duration;
+        self.file_type = EntryType::from_file_type(metadata.file_type());
+        self.size = metadata.len();
+        self.modified = metadata.modified().ok();
+        self.changed = u64::try_from(metadata.ctime())
+            .ok()
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::new(secs, metadata.ctime_nsec() as u32));
+        self.mode = metadata.mode();
+        self.uid = metadata.uid();
+        self.gid = metadata.gid();
+        self.nlink = metadata.nlink();
+        self.ino = metadata.ino();
+    }
+
+    #[cfg(not(unix))]
+    fn fill_metadata(&mut self, metadata: &Metadata) {
+        self.file_type = EntryType::from_file_type(metadata.file_type());
+        self.size = metadata.len();
+        self.modified = metadata.modified().ok();
+        self.changed = metadata.created().ok();
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The file operations that can fail, the error messages are named after them
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Operation {
+    ReadDir,
+    ChangeDir,
+    Select,
+    CreateFile,
+    CreateFolder,
+    Touch,
+    Copy,
+    Rename,
+    Delete,
+    Trash,
+    Open,
+    SaveHistory,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            Operation::ReadDir => "reading",
+            Operation::ChangeDir => "changing into",
+            Operation::Select => "selecting",
+            Operation::CreateFile => "creating",
+            Operation::CreateFolder => "creating the folder",
+            Operation::Touch => "touching",
+            Operation::Copy => "copying",
+            Operation::Rename => "renaming",
+            Operation::Delete => "deleting",
+            Operation::Trash => "moving to the trash",
+            Operation::Open => "opening",
+            Operation::SaveHistory => "saving the history to",
+        };
+        f.write_str(verb)
+    }
+}
+
+/// Everything that can go wrong in the explorer, with what was done when it happened
+#[derive(Debug)]
+pub enum Error {
+    /// operation failed at path
+    Io {
+        operation: Operation,
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// a program couldn't be started
+    Launch { program: String, source: io::Error },
+    /// a config file couldn't be read, the defaults are used instead
+    Config(String),
+    /// a line of the command line that can't be carried out
+    Command(String),
+    /// the action isn't allowed right now, e.g. deleting while choosing files
+    Refused(String),
+}
+
+impl Error {
+    pub fn io(operation: Operation, path: impl AsRef<Path>, source: io::Error) -> Error {
+        Error::Io {
+            operation,
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    /// the path the error happened at, if it was a file operation
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Io { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io {
+                operation,
+                path,
+                source,
+            } => write!(f, "{} {}: {}", operation, path.display(), source),
+            Error::Launch { program, source } => write!(f, "{}: {}", program, source),
+            Error::Config(text) | Error::Command(text) | Error::Refused(text) => f.write_str(text),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::Launch { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Adds the operation and the path to io errors, like `fs::copy(a, b).context(Operation::Copy, a)`
+pub trait Context<T> {
+    fn context(self, operation: Operation, path: impl AsRef<Path>) -> Result<T>;
+}
+
+impl<T> Context<T> for io::Result<T> {
+    fn context(self, operation: Operation, path: impl AsRef<Path>) -> Result<T> {
+        self.map_err(|e| Error::io(operation, path, e))
+    }
+}
@@ -0,0 +1,164 @@
+use crate::dir_loader::DirLoadEvent;
+use crate::disk_usage::DuNode;
+use crate::preview::Preview;
+use crate::shell::CommandOutput;
+use crossterm::event::{self, Event};
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, RecvError, Sender, channel};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// how often LoopEvent::Tick is sent
+const TICK_RATE: Duration = Duration::from_millis(250);
+/// the input thread checks this often whether the loop is still running
+const INPUT_POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Messages from background threads
+pub enum WorkerMessage {
+    /// progress of reading a directory
+    DirLoad(DirLoadEvent),
+    /// the recursive size of a directory was calculated, by the calculation with the given
+    /// generation of the cached sizes
+    DirSize(u64, PathBuf, u64),
+    /// a disk usage scan of the given root, started at the given generation, finished
+    DiskUsage(u64, PathBuf, DuNode),
+    /// the watched directory changed, the paths are the entries that changed or the directory
+    DirChanged(Vec<PathBuf>),
+    /// the preview of the path was read by the load with the given generation
+    Preview(u64, PathBuf, Preview),
+    /// the file or directory shown in the preview changed
+    PreviewChanged(PathBuf),
+    /// a shell command of the `!` prompt finished
+    CommandOutput(CommandOutput),
+}
+
+pub enum LoopEvent {
+    /// terminal input, including resize events
+    Input(Event),
+    Tick,
+    Worker(WorkerMessage),
+    /// reading terminal input failed
+    InputError(io::Error),
+}
+
+/// Multiplexes terminal input, a periodic tick and messages of worker threads into one channel.
+/// Every source runs on its own thread, so waiting for the next event never misses one of them
+pub struct EventLoop {
+    receiver: Receiver<LoopEvent>,
+    worker_sender: Sender<WorkerMessage>,
+    input_pause: Arc<InputPause>,
+}
+
+/// Lets the input thread stop reading the terminal while another program uses it
+#[derive(Default)]
+struct InputPause {
+    /// (pause requested, input thread is waiting)
+    state: Mutex<(bool, bool)>,
+    changed: Condvar,
+}
+
+impl InputPause {
+    /// called by the input thread between two polls, blocks while the input is paused
+    fn wait_while_paused(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !state.0 {
+            return;
+        }
+        state.1 = true;
+        self.changed.notify_all();
+        while state.0 {
+            state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.1 = false;
+    }
+}
+
+impl Default for EventLoop {
+    fn default() -> EventLoop {
+        EventLoop::new()
+    }
+}
+
+impl EventLoop {
+    pub fn new() -> EventLoop {
+        let (sender, receiver) = channel();
+
+        let input_sender = sender.clone();
+        let input_pause = Arc::new(InputPause::default());
+        let pause = input_pause.clone();
+        thread::spawn(move || {
+            loop {
+                pause.wait_while_paused();
+                let event = match event::poll(INPUT_POLL_TIMEOUT) {
+                    Ok(false) => continue,
+                    Ok(true) => event::read().map(LoopEvent::Input),
+                    Err(e) => Err(e),
+                };
+                let event = event.unwrap_or_else(LoopEvent::InputError);
+                if input_sender.send(event).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let tick_sender = sender.clone();
+        thread::spawn(move || {
+            loop {
+                thread::sleep(TICK_RATE);
+                if tick_sender.send(LoopEvent::Tick).is_err() {
+                    return;
+                }
+            }
+        });
+
+        //workers get their own channel, so they don't need to know about the loop
+        let (worker_sender, worker_receiver) = channel();
+        thread::spawn(move || {
+            while let Ok(message) = worker_receiver.recv() {
+                if sender.send(LoopEvent::Worker(message)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        EventLoop {
+            receiver,
+            worker_sender,
+            input_pause,
+        }
+    }
+
+    /// a sender for worker threads, their messages show up as LoopEvent::Worker
+    pub fn worker_sender(&self) -> Sender<WorkerMessage> {
+        self.worker_sender.clone()
+    }
+
+    /// stops reading terminal input and returns once the input thread stopped,
+    /// so a program started afterwards gets all keys
+    pub fn pause_input(&self) {
+        let pause = &self.input_pause;
+        let mut state = pause.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.0 = true;
+        while !state.1 {
+            state = pause.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    pub fn resume_input(&self) {
+        let pause = &self.input_pause;
+        pause.state.lock().unwrap_or_else(|e| e.into_inner()).0 = false;
+        pause.changed.notify_all();
+    }
+
+    /// block until the next event arrives
+    pub fn next(&self) -> Result<LoopEvent, RecvError> {
+        self.receiver.recv()
+    }
+
+    /// the next event if one is already waiting
+    pub fn try_next(&self) -> Option<LoopEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
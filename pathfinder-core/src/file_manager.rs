@@ -0,0 +1,807 @@
+use crate::column::Column;
+use crate::config::Config;
+use crate::dir_loader::{self, DirLoadEvent};
+use crate::dir_size;
+use crate::disk_usage::{self, DuNode};
+use crate::entry::Entry;
+use crate::error::{Context, Error, Operation, Result};
+use crate::event_loop::WorkerMessage;
+use crate::preview::{self, Preview};
+use crate::shell;
+use crate::sorting::{SortContext, Sorting};
+use crate::theme::Theme;
+use crate::trash::Trash;
+use crate::util::SizeFormat;
+use crate::watcher::DirWatcher;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs::create_dir;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::mpsc::Sender;
+use std::{fs, io};
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SortDir {
+    Unsorted,
+    Start,
+    End,
+}
+
+pub struct FileManager {
+    files: Vec<Entry>,
+    pub num_files: usize,
+    curr_sort: Sorting,
+    pub show_hidden: bool,
+    ///set in the chooser mode, the windows refuse destructive actions
+    pub read_only: bool,
+    pub dir_sorting: SortDir,
+    selection: HashSet<PathBuf>,
+    pub visible_columns: Vec<Column>,
+    pub size_format: SizeFormat,
+    pub theme: Theme,
+    ///where delete_selection moves files, None if the user has no home
+    pub trash: Option<Trash>,
+    current_dir: PathBuf,
+    dir_sizes: HashMap<PathBuf, u64>,
+    pending_dir_sizes: HashSet<PathBuf>,
+    ///counts the invalidations of dir_sizes, calculations are tagged with it when they start
+    size_generation: u64,
+    ///paths invalidated while calculations were running, with the generation they started
+    invalidated: Vec<(u64, PathBuf)>,
+    disk_usage: Option<(PathBuf, DuNode)>,
+    disk_usage_scanning: bool,
+    dir_load: Option<DirLoad>,
+    load_generation: u64,
+    watcher: Option<DirWatcher>,
+    ///every background thread reports through this sender, see handle_worker_message
+    worker_sender: Sender<WorkerMessage>,
+    ///set when worker messages changed the entries, they are sorted in sort_if_changed
+    listing_changed: bool,
+    listing_version: u64,
+    ///previews read in the background, by path. Kept until the path changes or the user
+    ///leaves the directory
+    previews: HashMap<PathBuf, Preview>,
+    ///counts the invalidations of previews, loads that started before one are dropped
+    preview_generation: u64,
+    ///shared with the running shell commands, cancel_commands sets it and starts a new one
+    command_cancel: Arc<AtomicBool>,
+}
+
+///a directory that is currently read in the background
+struct DirLoad {
+    cancel: Arc<AtomicBool>,
+    ///a refresh collects the entries and replaces the old ones at once
+    refresh: bool,
+    entries: Vec<Entry>,
+}
+
+impl FileManager {
+    /// changes the content of the FileManager to the Files of the new path.
+    /// The entries are read in the background and show up as they arrive.
+    /// If the new directory can't be opened, the FileManager stays in the old one
+    pub fn change_dir(&mut self, path_buf: PathBuf) -> Result<()> {
+        self.load_dir(&path_buf, false)
+            .context(Operation::ChangeDir, &path_buf)
+    }
+
+    ///update file_manager for current directory!
+    ///The old entries stay until the new ones are read completely, and if the directory
+    ///can't be read anymore, e.g. because it was deleted, until the user leaves it
+    pub fn update(&mut self) {
+        self.load_dir(Path::new("."), true).ok();
+    }
+
+    fn load_dir(&mut self, p: &Path, refresh: bool) -> io::Result<()> {
+        std::env::set_current_dir(p)?;
+        let entry_iter = match fs::read_dir(Path::new(".")) {
+            Ok(entry_iter) => entry_iter,
+            Err(e) => {
+                //going back can only fail if the old directory is gone as well
+                std::env::set_current_dir(&self.current_dir).ok();
+                return Err(e);
+            }
+        };
+        if let Ok(current_dir) = std::env::current_dir()
+            && current_dir != self.current_dir
+        {
+            if let Some(watcher) = &self.watcher {
+                watcher.watch(&current_dir);
+            }
+            self.current_dir = current_dir;
+            //only the entries of the current directory are previewed and watched
+            self.previews.clear();
+            self.preview_generation += 1;
+        }
+
+        //navigating away cancels the running load
+        if let Some(dir_load) = &self.dir_load {
+            dir_load.cancel.store(true, AtomicOrdering::Relaxed);
+        }
+        self.load_generation += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        dir_loader::spawn_dir_loader(
+            entry_iter,
+            self.current_dir.clone(),
+            self.load_generation,
+            cancel.clone(),
+            self.worker_sender.clone(),
+        );
+        self.dir_load = Some(DirLoad {
+            cancel,
+            refresh,
+            entries: Vec::new(),
+        });
+        if !refresh {
+            self.files.clear();
+            self.num_files = 0;
+        }
+        Ok(())
+    }
+
+    ///take over the result of a background thread.
+    ///Changed entries are only sorted by sort_if_changed, so many messages can be handled at once
+    pub fn handle_worker_message(&mut self, message: WorkerMessage) {
+        match message {
+            WorkerMessage::DirLoad(event) => {
+                self.listing_changed |= self.handle_dir_load_event(event);
+            }
+            WorkerMessage::DirSize(generation, path, size) => {
+                if self.pending_dir_sizes.remove(&path) {
+                    //the folder changed while it was calculated
+                    if !self.invalidated_since(generation, &path) {
+                        self.dir_sizes.insert(path, size);
+                        self.listing_changed = true;
+                    }
+                    self.forget_invalidations();
+                }
+            }
+            WorkerMessage::DiskUsage(generation, root, node) => {
+                self.disk_usage_scanning = false;
+                let mut sizes = disk_usage::dir_sizes(&root, &node);
+                sizes.retain(|dir, _size| !self.invalidated_since(generation, dir));
+                self.dir_sizes.extend(sizes);
+                self.forget_invalidations();
+                self.disk_usage = Some((root, node));
+                self.listing_changed = true;
+            }
+            WorkerMessage::DirChanged(paths) => {
+                for path in paths {
+                    self.invalidate(&path);
+                }
+                self.update();
+            }
+            WorkerMessage::Preview(generation, path, preview) => {
+                if generation == self.preview_generation {
+                    self.previews.insert(path, preview);
+                }
+            }
+            WorkerMessage::PreviewChanged(path) => self.invalidate(&path),
+            //the controller shows it
+            WorkerMessage::CommandOutput(_) => {}
+        }
+    }
+
+    ///sort the entries again if worker messages changed them
+    pub fn sort_if_changed(&mut self) {
+        if self.listing_changed {
+            self.listing_changed = false;
+            self.num_files = self.files.len();
+            self.sort(self.curr_sort);
+        }
+    }
+
+    fn handle_dir_load_event(&mut self, event: DirLoadEvent) -> bool {
+        let dir_load = match &mut self.dir_load {
+            Some(dir_load) => dir_load,
+            None => return false,
+        };
+        match event {
+            DirLoadEvent::Entries(generation, entries) => {
+                if generation != self.load_generation {
+                    return false;
+                }
+                let show_hidden = self.show_hidden;
+                let visible = entries
+                    .into_iter()
+                    .filter(|e| show_hidden || !e.name.as_encoded_bytes().starts_with(b"."));
+                if dir_load.refresh {
+                    dir_load.entries.extend(visible);
+                    false
+                } else {
+                    self.files.extend(visible);
+                    true
+                }
+            }
+            DirLoadEvent::Done(generation) => {
+                if generation != self.load_generation {
+                    return false;
+                }
+                if dir_load.refresh {
+                    self.files = std::mem::take(&mut dir_load.entries);
+                }
+                self.dir_load = None;
+                true
+            }
+        }
+    }
+
+    ///changes whenever the entries or their order changed
+    pub fn listing_version(&self) -> u64 {
+        self.listing_version
+    }
+
+    ///changes whenever cached previews were dropped, previews that were loading are
+    ///dropped as well then
+    pub fn preview_generation(&self) -> u64 {
+        self.preview_generation
+    }
+
+    ///the preview of path read by load_preview
+    pub fn preview(&self, path: &Path) -> Option<&Preview> {
+        self.previews.get(path)
+    }
+
+    ///reads the preview of path in the background and watches path, so a change drops it again.
+    ///The result can be taken with preview once its worker message was handled
+    pub fn load_preview(&self, path: &Path, max_bytes: u64, max_lines: usize) {
+        self.watch_preview(Some(path));
+        preview::spawn_preview_loader(
+            path.to_path_buf(),
+            max_bytes,
+            max_lines,
+            self.preview_generation,
+            self.worker_sender.clone(),
+        );
+    }
+
+    ///watches the file or directory shown in the preview, a change is reported as
+    ///WorkerMessage::PreviewChanged
+    pub fn watch_preview(&self, path: Option<&Path>) {
+        if let Some(watcher) = &self.watcher {
+            watcher.watch_preview(path);
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.dir_load.is_some()
+    }
+
+    ///creates and initializes a FileManager-struct
+    ///Calls change_dir on the CWD!
+    ///The results of background threads are sent to worker_sender
+    ///and have to be passed back to handle_worker_message
+    pub fn new(worker_sender: Sender<WorkerMessage>, config: &Config) -> FileManager {
+        let mut fm: FileManager = FileManager {
+            files: Vec::new(),
+            num_files: 0,
+            curr_sort: config.sorting,
+            show_hidden: config.show_hidden,
+            read_only: false,
+            dir_sorting: config.dir_sorting,
+            selection: HashSet::new(),
+            visible_columns: config.columns.clone(),
+            size_format: SizeFormat::Iec,
+            theme: Theme::default(),
+            trash: Trash::from_env(),
+            current_dir: PathBuf::from("."),
+            dir_sizes: HashMap::new(),
+            pending_dir_sizes: HashSet::new(),
+            size_generation: 0,
+            invalidated: Vec::new(),
+            disk_usage: None,
+            disk_usage_scanning: false,
+            dir_load: None,
+            load_generation: 0,
+            watcher: DirWatcher::spawn(worker_sender.clone()),
+            worker_sender,
+            listing_changed: false,
+            listing_version: 0,
+            previews: HashMap::new(),
+            preview_generation: 0,
+            command_cancel: Arc::new(AtomicBool::new(false)),
+        };
+        //an unreadable start directory stays empty, the user can still leave it
+        fm.load_dir(Path::new("."), false).ok();
+        fm
+    }
+
+    pub fn current_dir(&self) -> &Path {
+        &self.current_dir
+    }
+
+    pub fn sorting(&self) -> Sorting {
+        self.curr_sort
+    }
+
+    pub fn get_entries(&self) -> &Vec<Entry> {
+        &self.files
+    }
+
+    pub fn sort(&mut self, sort_mode: Sorting) {
+        self.listing_version += 1;
+        let context = SortContext {
+            dir_sizes: &self.dir_sizes,
+        };
+        match sort_mode {
+            Sorting::Ascending(key) => self.files.sort_by(|a, b| context.compare(key, a, b)),
+            Sorting::Descending(key) => self.files.sort_by(|b, a| context.compare(key, a, b)),
+            Sorting::Unsorted => {}
+        };
+        self.curr_sort = sort_mode;
+        match self.dir_sorting {
+            SortDir::Start => {
+                self.files.sort_by(FileManager::sort_dir_to_start);
+            }
+            SortDir::End => {
+                self.files
+                    .sort_by(|b, a| FileManager::sort_dir_to_start(a, b));
+            }
+            SortDir::Unsorted => {}
+        }
+    }
+
+    fn sort_dir_to_start(entry1: &Entry, entry2: &Entry) -> Ordering {
+        entry2.is_dir().cmp(&entry1.is_dir())
+    }
+
+    ///show or hide a column, the name column is always shown
+    pub fn toggle_column(&mut self, column: Column) {
+        if column == Column::Name {
+            return;
+        }
+        if self.visible_columns.contains(&column) {
+            self.visible_columns.retain(|c| *c != column);
+        } else {
+            self.visible_columns = Column::ALL
+                .into_iter()
+                .filter(|c| *c == column || self.visible_columns.contains(c))
+                .collect();
+        }
+    }
+
+    ///start calculating the recursive sizes of all directories in the current listing
+    ///which are neither cached nor already being calculated
+    pub fn calculate_dir_sizes(&mut self) {
+        let mut paths = Vec::new();
+        for entry in &self.files {
+            if entry.is_dir()
+                && !self.dir_sizes.contains_key(&entry.path)
+                && !self.pending_dir_sizes.contains(&entry.path)
+            {
+                self.pending_dir_sizes.insert(entry.path.clone());
+                paths.push(entry.path.clone());
+            }
+        }
+        if !paths.is_empty() {
+            dir_size::spawn_dir_size_worker(
+                paths,
+                self.size_generation,
+                self.worker_sender.clone(),
+            );
+        }
+    }
+
+    ///forgets what a change of path makes wrong: the cached sizes of path itself and the
+    ///folders above and below it, and the previews of path, its folder and what is below it.
+    ///Running size calculations go on, but their results for these folders are dropped
+    pub fn invalidate(&mut self, path: &Path) {
+        let path = self.current_dir.join(path);
+        self.dir_sizes
+            .retain(|dir, _size| !dir.starts_with(&path) && !path.starts_with(dir));
+        let parent = path.parent().unwrap_or(&path);
+        let previews = self.previews.len();
+        self.previews
+            .retain(|previewed, _preview| !previewed.starts_with(&path) && previewed != parent);
+        if self.previews.len() != previews {
+            self.preview_generation += 1;
+        }
+        self.size_generation += 1;
+        if !self.pending_dir_sizes.is_empty() || self.disk_usage_scanning {
+            self.invalidated.push((self.size_generation, path));
+        }
+    }
+
+    ///whether the size of dir was invalidated after the calculation with generation started
+    fn invalidated_since(&self, generation: u64, dir: &Path) -> bool {
+        self.invalidated.iter().any(|(invalidated, path)| {
+            *invalidated > generation && (dir.starts_with(path) || path.starts_with(dir))
+        })
+    }
+
+    ///the invalidations are only needed while calculations are running
+    fn forget_invalidations(&mut self) {
+        if self.pending_dir_sizes.is_empty() && !self.disk_usage_scanning {
+            self.invalidated.clear();
+        }
+    }
+
+    ///the cached recursive size of the directory at the absolute path
+    pub fn dir_size(&self, path: &Path) -> Option<u64> {
+        self.dir_sizes.get(path).copied()
+    }
+
+    pub fn is_dir_size_pending(&self, path: &Path) -> bool {
+        self.pending_dir_sizes.contains(path)
+    }
+
+    ///runs a shell script in the current directory in the background,
+    ///command is the line it was expanded from
+    pub fn run_captured(&self, command: String, script: OsString) {
+        shell::run_captured(
+            command,
+            script,
+            self.current_dir.clone(),
+            self.command_cancel.clone(),
+            self.worker_sender.clone(),
+        );
+    }
+
+    ///stops the shell commands started with run_captured, they report what they printed so far
+    pub fn cancel_commands(&mut self) {
+        self.command_cancel.store(true, AtomicOrdering::Relaxed);
+        self.command_cancel = Arc::new(AtomicBool::new(false));
+    }
+
+    ///start a disk usage scan of the current directory in the background
+    pub fn scan_disk_usage(&mut self) {
+        if self.disk_usage_scanning {
+            return;
+        }
+        self.disk_usage_scanning = true;
+        disk_usage::spawn_scan(
+            self.current_dir.clone(),
+            self.size_generation,
+            self.worker_sender.clone(),
+        );
+    }
+
+    ///the root and the tree of the last disk usage scan
+    pub fn disk_usage(&self) -> Option<(&Path, &DuNode)> {
+        self.disk_usage
+            .as_ref()
+            .map(|(root, node)| (root.as_path(), node))
+    }
+
+    pub fn is_disk_usage_scanning(&self) -> bool {
+        self.disk_usage_scanning
+    }
+
+    ///deletes the entry at path (relative to the root of the scan) from the disk
+    ///and from the scanned tree. Refused during a rescan, the old tree may be outdated by then
+    pub fn delete_from_disk_usage(&mut self, path: &[OsString]) -> Result<()> {
+        if path.is_empty() {
+            return Err(Error::Refused(
+                "refusing to delete the scanned directory".to_owned(),
+            ));
+        }
+        if self.disk_usage_scanning {
+            return Err(Error::Refused(
+                "wait for the scan to finish before deleting".to_owned(),
+            ));
+        }
+        let root = match &self.disk_usage {
+            Some((root, _node)) => root.clone(),
+            None => return Err(Error::Refused("there is no disk usage scan".to_owned())),
+        };
+        let full_path: PathBuf = path.iter().fold(root, |p, name| p.join(name));
+        self.delete(&full_path)?;
+        let removed = match &mut self.disk_usage {
+            Some((_root, node)) => node.remove(path).unwrap_or(0),
+            None => 0,
+        };
+        //the folders above only lost what was deleted
+        let smaller: Vec<(PathBuf, u64)> = full_path
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| {
+                Some((
+                    dir.to_path_buf(),
+                    self.dir_sizes.get(dir)?.checked_sub(removed)?,
+                ))
+            })
+            .collect();
+        self.invalidate(&full_path);
+        self.dir_sizes.extend(smaller);
+        self.selection.remove(&full_path);
+        self.update();
+        Ok(())
+    }
+
+    /// the selected paths, sorted so commands get them in a predictable order
+    pub fn selection(&self) -> Vec<PathBuf> {
+        let mut selection: Vec<PathBuf> = self.selection.iter().cloned().collect();
+        selection.sort();
+        selection
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    ///add file to selection
+    pub fn add_to_selection(&mut self, pb: PathBuf) -> Result<()> {
+        let full_path = std::path::absolute(&pb).context(Operation::Select, &pb)?;
+        self.selection.insert(full_path);
+        Ok(())
+    }
+
+    ///remove file from selection
+    pub fn remove_from_selection(&mut self, pb: PathBuf) -> Result<()> {
+        let full_path = std::path::absolute(&pb).context(Operation::Select, &pb)?;
+        self.selection.remove(&full_path);
+        Ok(())
+    }
+
+    pub fn is_selected(&self, path: &PathBuf) -> bool {
+        //entries already know their absolute path, don't ask the OS for the cwd every frame
+        if path.is_absolute() {
+            return self.selection.contains(path);
+        }
+        match std::path::absolute(path) {
+            Ok(full_path) => self.selection.contains(&full_path),
+            Err(_) => false,
+        }
+    }
+
+    ///moves the selected paths to the trash, stopping at the first error.
+    ///Trashed paths leave the selection, so trying again only trashes the rest
+    pub fn delete_selection(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for dest in self.selection() {
+            result = self.trash(&dest);
+            if result.is_err() {
+                break;
+            }
+            self.selection.remove(&dest);
+        }
+        self.update();
+        result
+    }
+
+    ///moves dest into the trash, from where it can be restored
+    pub fn trash(&mut self, dest: &Path) -> Result<()> {
+        let trash = self.trash.as_ref().ok_or_else(|| {
+            Error::Refused("there is no trash without a home directory".to_owned())
+        })?;
+        let trashed = trash.put(dest).context(Operation::Trash, dest)?;
+        self.invalidate(dest);
+        self.invalidate(&trashed);
+        Ok(())
+    }
+
+    ///deletes dest permanently
+    pub fn delete(&mut self, dest: &PathBuf) -> Result<()> {
+        if dest.is_dir() {
+            fs::remove_dir_all(dest).context(Operation::Delete, dest)
+        } else {
+            //files, links and special files, a missing path fails with NotFound
+            fs::remove_file(dest).context(Operation::Delete, dest)
+        }
+    }
+
+    ///number of selected files and directories whose name already exists in the current directory
+    pub fn paste_conflicts(&self) -> usize {
+        self.selection
+            .iter()
+            .filter_map(|src| src.file_name())
+            .filter(|name| Path::new(name).symlink_metadata().is_ok())
+            .count()
+    }
+
+    ///paste the content of copy_buffer into the current directory!
+    ///deep-copies directories, existing files are overwritten and existing directories merged.
+    ///Nothing is copied if a path would be pasted onto or into itself
+    pub fn paste(&mut self) -> Result<()> {
+        let current_dir = self.current_dir.clone();
+        let real_dir = fs::canonicalize(&current_dir).context(Operation::Copy, &current_dir)?;
+
+        let mut copies = Vec::new();
+        for src in &self.selection {
+            //the root directory has no name to paste it as
+            let src_name = match src.file_name() {
+                None => continue,
+                Some(name) => name,
+            };
+            let dest = current_dir.join(src_name);
+            let real_src = fs::canonicalize(src).context(Operation::Copy, src)?;
+            //copying a file onto itself would truncate it to nothing
+            if fs::canonicalize(&dest).is_ok_and(|real_dest| real_dest == real_src) {
+                return Err(Error::Refused(format!(
+                    "{} is already in this folder, it can't be pasted onto itself",
+                    src.display()
+                )));
+            }
+            if real_src.is_dir() && real_dir.starts_with(&real_src) {
+                return Err(Error::Refused(format!(
+                    "{} can't be pasted into itself",
+                    src.display()
+                )));
+            }
+            copies.push((src, dest));
+        }
+        let mut result = Ok(());
+        for (src, dest) in &copies {
+            result = copy_recursively(src, dest);
+            if result.is_err() {
+                break;
+            }
+        }
+        let dests: Vec<PathBuf> = copies.into_iter().map(|(_src, dest)| dest).collect();
+        for dest in dests {
+            self.invalidate(&dest);
+        }
+        self.update();
+        result?;
+        Ok(())
+    }
+
+    pub fn get_entry_at_index(&self, index: usize) -> io::Result<&Entry> {
+        let entry = self.get_entries().get(index);
+        match entry {
+            Some(entry) => Ok(entry),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, "wrong index")),
+        }
+    }
+
+    pub fn create_file(&mut self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(Operation::CreateFolder, parent)?;
+        }
+        fs::File::create(&path).context(Operation::CreateFile, &path)?;
+        self.invalidate(&path);
+        self.update();
+        Ok(())
+    }
+
+    pub fn create_folder(&mut self, path: PathBuf) -> Result<()> {
+        fs::create_dir_all(&path).context(Operation::CreateFolder, &path)?;
+        self.invalidate(&path);
+        self.update();
+        Ok(())
+    }
+
+    /// creates path like create_file, existing files only get a new modification time
+    pub fn touch(&mut self, path: PathBuf) -> Result<()> {
+        if !path.exists() {
+            return self.create_file(path);
+        }
+        //opened read only, so directories can be touched as well
+        fs::File::open(&path)
+            .and_then(|file| file.set_modified(std::time::SystemTime::now()))
+            .context(Operation::Touch, &path)?;
+        self.invalidate(&path);
+        self.update();
+        Ok(())
+    }
+
+    /// renames or moves from to to, refusing to replace an existing file
+    pub fn rename(&mut self, from: &Path, to: &Path) -> Result<()> {
+        if to.symlink_metadata().is_ok() {
+            //the error points at the file that is in the way
+            return Err(Error::io(
+                Operation::Rename,
+                to,
+                io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("already exists, {} was not renamed", from.display()),
+                ),
+            ));
+        }
+        match fs::rename(from, to) {
+            //another file system, the way paste copies followed by a delete
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => move_across_devices(from, to)?,
+            result => result.context(Operation::Rename, from)?,
+        }
+        self.invalidate(from);
+        self.invalidate(to);
+        self.update();
+        Ok(())
+    }
+}
+
+/// creates the directory at path unless there is one already, pasting merges into it
+fn create_dir_to_merge(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        return Ok(());
+    }
+    create_dir(path)
+}
+
+/// copies the file or directory src to dest, deep-copying directories.
+/// Existing files are overwritten and existing directories merged
+fn copy_recursively(src: &Path, dest: &Path) -> Result<()> {
+    if src.is_file() {
+        fs::copy(src, dest).context(Operation::Copy, src)?;
+    }
+    //copying the directory and recursively copy it's content into the new directory
+    else if src.is_dir() {
+        create_dir_to_merge(dest).context(Operation::CreateFolder, dest)?;
+
+        let mut stack: Vec<PathBuf> = Vec::new(); //contains relative paths within the source directory
+        stack.push(PathBuf::from(".")); //start with the root of the source directory
+
+        loop {
+            let current_relative_path = match stack.pop() {
+                None => break, //stack is empty
+                Some(path) => path,
+            };
+
+            let current_src_path = src.join(&current_relative_path);
+            let entry_iter =
+                fs::read_dir(&current_src_path).context(Operation::ReadDir, &current_src_path)?;
+
+            for entry_res in entry_iter {
+                let entry = match entry_res {
+                    Err(_e) => continue,
+                    Ok(entry) => entry,
+                };
+                let file_type = match entry.file_type() {
+                    Err(_e) => continue,
+                    Ok(file_type) => file_type,
+                };
+
+                let relative_entry_path = current_relative_path.join(entry.file_name());
+                let src_entry = src.join(&relative_entry_path);
+                let dest_entry = dest.join(&relative_entry_path);
+
+                if file_type.is_dir() {
+                    create_dir_to_merge(&dest_entry)
+                        .context(Operation::CreateFolder, &dest_entry)?;
+                    stack.push(relative_entry_path);
+                } else if file_type.is_file() {
+                    fs::copy(&src_entry, &dest_entry).context(Operation::Copy, &src_entry)?;
+                } else if file_type.is_symlink() {
+                    let link_target =
+                        fs::read_link(&src_entry).context(Operation::Copy, &src_entry)?;
+                    //a link replaces what is in the way, like fs::copy does for files
+                    if dest_entry.symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+                        fs::remove_file(&dest_entry).context(Operation::Delete, &dest_entry)?;
+                    }
+                    #[cfg(unix)]
+                    let linked = std::os::unix::fs::symlink(link_target, dest_entry);
+                    #[cfg(windows)]
+                    let linked = if src_entry.is_dir() {
+                        std::os::windows::fs::symlink_dir(link_target, dest_entry)
+                    } else {
+                        std::os::windows::fs::symlink_file(link_target, dest_entry)
+                    };
+                    linked.context(Operation::Copy, &src_entry)?;
+                } else {
+                    return Err(Error::io(
+                        Operation::Copy,
+                        &src_entry,
+                        io::Error::new(io::ErrorKind::Unsupported, "special files can't be copied"),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// moves from to to on another file system, where it can't simply be renamed.
+/// A link is moved as it is, instead of copying what it points to
+fn move_across_devices(from: &Path, to: &Path) -> Result<()> {
+    let metadata = from.symlink_metadata().context(Operation::Rename, from)?;
+    if metadata.is_symlink() {
+        let link_target = fs::read_link(from).context(Operation::Copy, from)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(link_target, to).context(Operation::Copy, from)?;
+        #[cfg(windows)]
+        if from.is_dir() {
+            std::os::windows::fs::symlink_dir(link_target, to).context(Operation::Copy, from)?;
+        } else {
+            std::os::windows::fs::symlink_file(link_target, to).context(Operation::Copy, from)?;
+        }
+        return fs::remove_file(from).context(Operation::Delete, from);
+    }
+    copy_recursively(from, to)?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(from).context(Operation::Delete, from)
+    } else {
+        fs::remove_file(from).context(Operation::Delete, from)
+    }
+}
@@ -0,0 +1,456 @@
+use crate::sorting::Sorting;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Everything that can be bound to a key in the explorer
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    MoveDown,
+    MoveUp,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    First,
+    Last,
+    EnterDir,
+    ParentDir,
+    ToggleSelection,
+    ClearSelection,
+    Paste,
+    Delete,
+    NewFile,
+    ToggleHidden,
+    CycleDirSorting,
+    OpenSorting,
+    OpenColumns,
+    CycleSizeFormat,
+    CalculateDirSizes,
+    DiskUsage,
+    Help,
+    CommandPalette,
+    CommandLine,
+    OpenWith,
+    Shell,
+    ShellPrompt,
+    CancelCommands,
+    ErrorLog,
+    SortBy(Sorting),
+    /// an action of the [[actions]] of config.toml, by its index
+    Custom(usize),
+    /// passes the selection to the program that started the chooser mode
+    Choose,
+}
+
+impl Action {
+    /// all actions without a parameter
+    pub const ALL: [Action; 31] = [
+        Action::Quit,
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::First,
+        Action::Last,
+        Action::EnterDir,
+        Action::ParentDir,
+        Action::ToggleSelection,
+        Action::ClearSelection,
+        Action::Paste,
+        Action::Delete,
+        Action::NewFile,
+        Action::ToggleHidden,
+        Action::CycleDirSorting,
+        Action::OpenSorting,
+        Action::OpenColumns,
+        Action::CycleSizeFormat,
+        Action::CalculateDirSizes,
+        Action::DiskUsage,
+        Action::Help,
+        Action::CommandPalette,
+        Action::CommandLine,
+        Action::OpenWith,
+        Action::Shell,
+        Action::ShellPrompt,
+        Action::CancelCommands,
+        Action::ErrorLog,
+    ];
+
+    /// every action, including the ones with a parameter
+    pub fn all() -> impl Iterator<Item = Action> {
+        Action::ALL
+            .into_iter()
+            .chain(Sorting::modes().map(Action::SortBy))
+    }
+
+    /// the name used in keys.toml
+    pub fn name(&self) -> String {
+        let name = match self {
+            Action::Quit => "quit",
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::HalfPageDown => "half_page_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::EnterDir => "enter_dir",
+            Action::ParentDir => "parent_dir",
+            Action::ToggleSelection => "toggle_selection",
+            Action::ClearSelection => "clear_selection",
+            Action::Paste => "paste",
+            Action::Delete => "delete",
+            Action::NewFile => "new_file",
+            Action::ToggleHidden => "toggle_hidden",
+            Action::CycleDirSorting => "cycle_dir_sorting",
+            Action::OpenSorting => "sort",
+            Action::OpenColumns => "columns",
+            Action::CycleSizeFormat => "cycle_size_format",
+            Action::CalculateDirSizes => "calculate_dir_sizes",
+            Action::DiskUsage => "disk_usage",
+            Action::Help => "help",
+            Action::CommandPalette => "command_palette",
+            Action::CommandLine => "command_line",
+            Action::OpenWith => "open_with",
+            Action::Shell => "shell",
+            Action::ShellPrompt => "shell_command",
+            Action::CancelCommands => "cancel_commands",
+            Action::ErrorLog => "errors",
+            Action::SortBy(sorting) => return format!("sort_by {}", sorting.name()),
+            //bound in config.toml, they have no name of their own
+            Action::Custom(index) => return format!("custom_action {}", index),
+            Action::Choose => "choose",
+        };
+        name.to_owned()
+    }
+
+    /// actions that change or delete files or run commands that can, they are disabled in the
+    /// chooser mode
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Action::Paste
+                | Action::Delete
+                | Action::Shell
+                | Action::ShellPrompt
+                | Action::OpenWith
+                | Action::Custom(_)
+        )
+    }
+
+    /// the inverse of name, e.g. `paste`, `sort_by mtime desc` or `custom_action 0`
+    pub fn from_name(name: &str) -> Option<Action> {
+        if let Some(sorting) = name.strip_prefix("sort_by ") {
+            return Sorting::parse(sorting).ok().map(Action::SortBy);
+        }
+        if let Some(index) = name.strip_prefix("custom_action ") {
+            return index.parse().ok().map(Action::Custom);
+        }
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// what the help popup and the command palette show
+    pub fn description(&self) -> String {
+        let description = match self {
+            Action::Quit => "quit file explorer",
+            Action::MoveDown => "move down",
+            Action::MoveUp => "move up",
+            Action::PageDown => "scroll a page down",
+            Action::PageUp => "scroll a page up",
+            Action::HalfPageDown => "scroll half a page down",
+            Action::HalfPageUp => "scroll half a page up",
+            Action::First => "jump to first entry",
+            Action::Last => "jump to last entry",
+            Action::EnterDir => "open file or enter folder",
+            Action::ParentDir => "go to parent folder",
+            Action::ToggleSelection => "toggle selection",
+            Action::ClearSelection => "clear selection",
+            Action::Paste => "paste selection",
+            Action::Delete => "delete selection",
+            Action::NewFile => "create new file",
+            Action::ToggleHidden => "toggle hidden files",
+            Action::CycleDirSorting => "change folder positions",
+            Action::OpenSorting => "open sorting popup",
+            Action::OpenColumns => "choose columns",
+            Action::CycleSizeFormat => "change size format",
+            Action::CalculateDirSizes => "calculate folder sizes",
+            Action::DiskUsage => "disk usage analyzer",
+            Action::Help => "show key mappings",
+            Action::CommandPalette => "open command palette",
+            Action::CommandLine => "open command line",
+            Action::OpenWith => "choose the program to open a file with",
+            Action::Shell => "open a shell in this folder",
+            Action::ShellPrompt => "run a shell command",
+            Action::CancelCommands => "stop the running shell commands",
+            Action::ErrorLog => "show the errors of this session",
+            Action::Custom(_) => "run a custom action",
+            Action::Choose => "choose the selection and quit",
+            Action::SortBy(sorting) => return format!("sort by {}", sorting.label()),
+        };
+        description.to_owned()
+    }
+}
+
+/// A key together with its modifiers.
+/// Shift is folded into the character, so <G> and <S-g> are the same chord
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        let mut code = code;
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if let KeyCode::Char(c) = code {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                code = KeyCode::Char(c.to_ascii_uppercase());
+            }
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        //some terminals report Shift-Tab as BackTab
+        if code == KeyCode::BackTab {
+            code = KeyCode::Tab;
+            modifiers.insert(KeyModifiers::SHIFT);
+        }
+        KeyChord { code, modifiers }
+    }
+
+    /// parses chords like `j`, `G`, `C-d`, `ctrl-shift-p`, `A-Enter` or `PageDown`
+    pub fn parse(text: &str) -> Result<KeyChord, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        //a single character is always a key, even "-"
+        while rest.chars().count() > 1
+            && let Some((modifier, key)) = rest.split_once('-')
+        {
+            match modifier.to_ascii_lowercase().as_str() {
+                "c" | "ctrl" => modifiers.insert(KeyModifiers::CONTROL),
+                "a" | "alt" | "m" | "meta" => modifiers.insert(KeyModifiers::ALT),
+                "s" | "shift" => modifiers.insert(KeyModifiers::SHIFT),
+                _ => return Err(format!("unknown modifier \"{}\" in \"{}\"", modifier, text)),
+            }
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" | "bs" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                key => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{}\" in \"{}\"", rest, text)),
+                },
+            },
+        };
+        Ok(KeyChord::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key_event: KeyEvent) -> KeyChord {
+        KeyChord::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            code => write!(f, "{}", code),
+        }
+    }
+}
+
+pub enum KeyMatch {
+    Action(Action),
+    /// the keys are the start of at least one longer sequence
+    Prefix,
+    None,
+}
+
+/// Maps key sequences to actions.
+/// The defaults can be changed in keys.toml, e.g. `"C-n" = "move_down"`, `"g g" = "first"`
+/// or `"x" = "none"` to remove a binding. A key that is bound alone can't start a sequence
+pub struct KeyMap {
+    bindings: Vec<(Vec<KeyChord>, Action)>,
+}
+
+const DEFAULT_BINDINGS: [(&str, Action); 41] = [
+    ("q", Action::Quit),
+    ("j", Action::MoveDown),
+    ("Down", Action::MoveDown),
+    ("k", Action::MoveUp),
+    ("Up", Action::MoveUp),
+    ("PageDown", Action::PageDown),
+    ("PageUp", Action::PageUp),
+    ("C-d", Action::HalfPageDown),
+    ("C-u", Action::HalfPageUp),
+    ("g g", Action::First),
+    ("Home", Action::First),
+    ("G", Action::Last),
+    ("End", Action::Last),
+    ("l", Action::EnterDir),
+    ("Enter", Action::EnterDir),
+    ("o", Action::OpenWith),
+    ("S", Action::Shell),
+    ("!", Action::ShellPrompt),
+    ("K", Action::CancelCommands),
+    ("E", Action::ErrorLog),
+    ("Right", Action::EnterDir),
+    ("h", Action::ParentDir),
+    ("Left", Action::ParentDir),
+    ("y", Action::ToggleSelection),
+    ("c", Action::ClearSelection),
+    ("v", Action::Paste),
+    ("x", Action::Delete),
+    ("n", Action::NewFile),
+    (".", Action::ToggleHidden),
+    ("d", Action::CycleDirSorting),
+    ("s", Action::OpenSorting),
+    ("i", Action::OpenColumns),
+    ("b", Action::CycleSizeFormat),
+    ("z", Action::CalculateDirSizes),
+    ("u", Action::DiskUsage),
+    ("m", Action::Help),
+    ("?", Action::Help),
+    ("F1", Action::Help),
+    (":", Action::CommandLine),
+    ("C-S-p", Action::CommandPalette),
+    ("C-p", Action::CommandPalette),
+];
+
+impl KeyMap {
+    /// the built in bindings merged with the ones of keys.toml in the config directory
+    pub fn load() -> Result<KeyMap, String> {
+        let mut keymap = KeyMap::default();
+        let path = match crate::util::config_dir() {
+            Some(dir) => dir.join("keys.toml"),
+            None => return Ok(keymap),
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(keymap),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        keymap
+            .merge_toml(&text)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(keymap)
+    }
+
+    /// adds the bindings of a keys.toml, replacing bindings of the same key sequence
+    pub fn merge_toml(&mut self, text: &str) -> Result<(), String> {
+        let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+        for (keys, value) in table {
+            let name = match value.as_str() {
+                Some(name) => name,
+                None => return Err(format!("the action for \"{}\" has to be a string", keys)),
+            };
+            if name == "none" {
+                let sequence = parse_sequence(&keys)?;
+                self.bindings.retain(|(bound, _)| *bound != sequence);
+                continue;
+            }
+            match Action::from_name(name) {
+                Some(action) => self.bind(&keys, action)?,
+                None => return Err(format!("unknown action \"{}\" for \"{}\"", name, keys)),
+            }
+        }
+        Ok(())
+    }
+
+    /// binds a key sequence like `g g` to action, replacing its old binding
+    pub fn bind(&mut self, keys: &str, action: Action) -> Result<(), String> {
+        let sequence = parse_sequence(keys)?;
+        self.bindings.retain(|(bound, _)| *bound != sequence);
+        self.bindings.push((sequence, action));
+        Ok(())
+    }
+
+    pub fn lookup(&self, keys: &[KeyChord]) -> KeyMatch {
+        let mut is_prefix = false;
+        for (sequence, action) in &self.bindings {
+            if sequence.as_slice() == keys {
+                return KeyMatch::Action(*action);
+            }
+            is_prefix |= sequence.starts_with(keys);
+        }
+        if is_prefix {
+            KeyMatch::Prefix
+        } else {
+            KeyMatch::None
+        }
+    }
+
+    /// all key sequences bound to action, formatted like `<g g>`
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(sequence, _)| {
+                let chords: Vec<String> = sequence.iter().map(|c| c.to_string()).collect();
+                format!("<{}>", chords.join(" "))
+            })
+            .collect()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> KeyMap {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .map(|(keys, action)| {
+                let keys = parse_sequence(keys).expect("the default bindings are valid");
+                (keys, *action)
+            })
+            .collect();
+        KeyMap { bindings }
+    }
+}
+
+/// chords of a sequence are separated by spaces, e.g. `g g`
+fn parse_sequence(text: &str) -> Result<Vec<KeyChord>, String> {
+    let sequence: Vec<KeyChord> = text
+        .split_whitespace()
+        .map(KeyChord::parse)
+        .collect::<Result<_, _>>()?;
+    if sequence.is_empty() {
+        return Err("empty key sequence".to_owned());
+    }
+    Ok(sequence)
+}
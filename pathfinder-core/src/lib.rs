@@ -0,0 +1,24 @@
+//! The file explorer behind pathfinder: the FileManager with its file operations and sorting,
+//! and the ratatui ui, which other terminal programs can embed
+
+pub mod column;
+pub mod command;
+pub mod config;
+mod dir_loader;
+mod dir_size;
+pub mod disk_usage;
+pub mod entry;
+pub mod error;
+pub mod event_loop;
+pub mod file_manager;
+pub mod keymap;
+pub mod opener;
+mod preview;
+pub mod shell;
+pub mod sorting;
+mod test;
+pub mod theme;
+pub mod trash;
+pub mod ui;
+pub mod util;
+mod watcher;
@@ -0,0 +1,333 @@
+use crate::command;
+use crate::config::{Config, OpenRule};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// the opener of the desktop, used if neither a rule nor the editor fits
+#[cfg(target_os = "macos")]
+const SYSTEM_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const SYSTEM_OPENER: &str = "xdg-open";
+
+/// How a file is opened
+pub enum Launch {
+    /// the program needs the terminal, the ui is suspended until it exits
+    Terminal(Command),
+    /// a gui program that runs next to the explorer
+    Detached(Command),
+}
+
+/// picks the program for path: the first matching [[open]] rule of the config,
+/// the editor for text files and the system opener for everything else
+pub fn launch_for(path: &Path, config: &Config) -> Result<Launch, String> {
+    let mime = mime_type(path);
+    let files = [path.to_path_buf()];
+    if let Some(rule) = config
+        .open
+        .iter()
+        .find(|rule| rule_matches(rule, path, &mime))
+    {
+        return launch(&rule.command, rule.terminal, path, &files);
+    }
+    if is_text(&mime)
+        && let Some(editor) = editor(config)
+    {
+        return launch(&editor, true, path, &files);
+    }
+    let opener = config.opener.as_deref().unwrap_or(SYSTEM_OPENER);
+    launch(opener, false, path, &files)
+}
+
+/// builds the command of template, see build_command
+pub fn launch(
+    template: &str,
+    terminal: bool,
+    file: &Path,
+    files: &[PathBuf],
+) -> Result<Launch, String> {
+    let command = build_command(template, file, files)?;
+    Ok(if terminal {
+        Launch::Terminal(command)
+    } else {
+        Launch::Detached(command)
+    })
+}
+
+/// A program the "open with" popup offers
+#[derive(Debug, PartialEq)]
+pub struct Candidate {
+    pub name: String,
+    /// a command line with `%f`/`%F` placeholders
+    pub command: String,
+    pub terminal: bool,
+}
+
+/// every program that can open path: the matching [[open]] rules, the applications of
+/// .desktop files in application_dirs that handle its mime type, the editor and the system opener
+pub fn candidates(path: &Path, config: &Config, application_dirs: &[PathBuf]) -> Vec<Candidate> {
+    let mime = mime_type(path);
+    let mut candidates: Vec<Candidate> = config
+        .open
+        .iter()
+        .filter(|rule| rule_matches(rule, path, &mime))
+        .map(|rule| Candidate {
+            name: rule.command.clone(),
+            command: rule.command.clone(),
+            terminal: rule.terminal,
+        })
+        .collect();
+    candidates.extend(desktop_entries(application_dirs, &mime));
+    if let Some(editor) = editor(config) {
+        candidates.push(Candidate {
+            name: format!("editor ({})", editor),
+            command: editor,
+            terminal: true,
+        });
+    }
+    let opener = config.opener.clone().unwrap_or(SYSTEM_OPENER.to_owned());
+    candidates.push(Candidate {
+        name: opener.clone(),
+        command: opener,
+        terminal: false,
+    });
+    candidates
+}
+
+/// the `applications` directories of $XDG_DATA_HOME and $XDG_DATA_DIRS, most important first
+pub fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => dirs.push(PathBuf::from(dir)),
+        _ => dirs
+            .extend(std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))),
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or("/usr/local/share:/usr/share".to_owned());
+    dirs.extend(data_dirs.split(':').map(PathBuf::from));
+    dirs.into_iter()
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// The applications of the .desktop files in dirs that can open files of mime.
+/// A file shadows files with the same name in later dirs, like the XDG spec says
+pub fn desktop_entries(dirs: &[PathBuf], mime: &str) -> Vec<Candidate> {
+    let mut seen = std::collections::HashSet::new();
+    let mut candidates = Vec::new();
+    for dir in dirs {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_e) => continue,
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "desktop"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            if !seen.insert(path.file_name().map(|name| name.to_owned())) {
+                continue;
+            }
+            //broken or unreadable files are skipped, like desktop environments do
+            if let Ok(text) = fs::read_to_string(&path)
+                && let Some(candidate) = parse_desktop_entry(&text, mime)
+            {
+                candidates.push(candidate);
+            }
+        }
+    }
+    candidates
+}
+
+/// the application of a .desktop file, if it is shown and handles mime
+fn parse_desktop_entry(text: &str, mime: &str) -> Option<Candidate> {
+    let mut in_entry = false;
+    let mut fields = std::collections::HashMap::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry && let Some((key, value)) = line.split_once('=') {
+            //localized keys like Name[de] are ignored
+            fields.insert(key.trim(), value.trim());
+        }
+    }
+    let hidden = |key| fields.get(key).is_some_and(|v| *v == "true");
+    if fields.get("Type") != Some(&"Application") || hidden("NoDisplay") || hidden("Hidden") {
+        return None;
+    }
+    let handles_mime = fields
+        .get("MimeType")?
+        .split(';')
+        .any(|pattern| !pattern.is_empty() && crate::util::glob_match(pattern, mime));
+    if !handles_mime {
+        return None;
+    }
+    Some(Candidate {
+        name: fields.get("Name")?.to_string(),
+        command: exec_to_template(fields.get("Exec")?),
+        terminal: hidden("Terminal"),
+    })
+}
+
+/// turns the field codes of an Exec line into the placeholders of build_command.
+/// Urls are passed as files, the codes for icons and names are dropped
+fn exec_to_template(exec: &str) -> String {
+    let mut template = String::new();
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            template.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('f') | Some('u') => template.push_str("%f"),
+            Some('F') | Some('U') => template.push_str("%F"),
+            Some('%') => template.push('%'),
+            _ => {}
+        }
+    }
+    template
+}
+
+/// config.editor, $VISUAL or $EDITOR
+fn editor(config: &Config) -> Option<String> {
+    config
+        .editor
+        .clone()
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+}
+
+/// a rule matches by extension or by a mime pattern like `image/*`
+fn rule_matches(rule: &OpenRule, path: &Path, mime: &str) -> bool {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    let by_extension = extension.as_ref().is_some_and(|e| {
+        rule.ext
+            .iter()
+            .any(|rule_ext| rule_ext.to_lowercase() == *e)
+    });
+    let by_mime = rule
+        .mime
+        .as_ref()
+        .is_some_and(|pattern| crate::util::glob_match(pattern, mime));
+    by_extension || by_mime
+}
+
+/// Turns a command line like `nvim -O %F` into a Command.
+/// `%f` is replaced by file and a `%F` word by files, without placeholders file is appended
+pub fn build_command(template: &str, file: &Path, files: &[PathBuf]) -> Result<Command, String> {
+    let (words, _) = command::split_words(template)?;
+    let mut words = words.into_iter().map(|(_, word)| word);
+    let program = words.next().ok_or("empty command")?;
+    let mut command = Command::new(program);
+    let mut has_placeholder = false;
+    for word in words {
+        if word == "%F" {
+            command.args(files);
+            has_placeholder = true;
+        } else if word == "%f" {
+            //non utf-8 names only survive as a whole word
+            command.arg(file);
+            has_placeholder = true;
+        } else if word.contains("%f") {
+            command.arg(word.replace("%f", &file.to_string_lossy()));
+            has_placeholder = true;
+        } else {
+            command.arg(word);
+        }
+    }
+    if !has_placeholder {
+        command.arg(file);
+    }
+    Ok(command)
+}
+
+/// starts command without a connection to the terminal, it keeps running after the explorer quits
+pub fn spawn_detached(command: &mut Command) -> io::Result<()> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    //its own process group keeps Ctrl-C in the explorer away from it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let mut child = command.spawn()?;
+    //wait for it in the background, so it doesn't stay a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// the name of the program command runs, for error messages
+pub fn program_name(command: &Command) -> String {
+    command.get_program().to_string_lossy().into_owned()
+}
+
+/// Guesses the mime type from the extension. Unknown files are `text/plain` if their start
+/// looks like text, `application/octet-stream` otherwise
+pub fn mime_type(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mime = match extension.as_str() {
+        "txt" | "log" | "md" | "rst" | "ini" | "cfg" | "conf" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "rs" => "text/x-rust",
+        "c" | "h" => "text/x-c",
+        "cpp" | "cc" | "hpp" => "text/x-c++",
+        "py" => "text/x-python",
+        "sh" | "bash" | "zsh" => "text/x-shellscript",
+        "js" => "text/javascript",
+        "toml" => "application/toml",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "opus" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" => "application/gzip",
+        _ if looks_like_text(path) => "text/plain",
+        _ => "application/octet-stream",
+    };
+    mime.to_owned()
+}
+
+/// text types that aren't called text/...
+fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/toml" | "application/json" | "application/xml" | "application/yaml"
+        )
+}
+
+/// the same guess the preview makes, files without a zero byte in their first kilobyte are text
+fn looks_like_text(path: &Path) -> bool {
+    let mut bytes = Vec::new();
+    match fs::File::open(path).and_then(|file| file.take(1024).read_to_end(&mut bytes)) {
+        Ok(_) => !bytes.contains(&0),
+        Err(_e) => false,
+    }
+}
@@ -0,0 +1,88 @@
+use crate::event_loop::WorkerMessage;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// The lines of a preview and how many were asked for
+pub struct Preview {
+    pub lines: Vec<String>,
+    pub max_lines: usize,
+}
+
+impl Preview {
+    /// whether the preview has all lines that fit into max_lines
+    pub fn covers(&self, max_lines: usize) -> bool {
+        self.max_lines >= max_lines || self.lines.len() < self.max_lines
+    }
+}
+
+/// reads the preview of path on a background thread, so slow mounts and huge folders
+/// don't block the ui. The result is tagged with generation
+pub fn spawn_preview_loader(
+    path: PathBuf,
+    max_bytes: u64,
+    max_lines: usize,
+    generation: u64,
+    sender: Sender<WorkerMessage>,
+) {
+    thread::spawn(move || {
+        let lines = preview_lines(&path, max_bytes, max_lines);
+        let preview = Preview { lines, max_lines };
+        let _ = sender.send(WorkerMessage::Preview(generation, path, preview));
+    });
+}
+
+/// the lines shown in the preview of path: the start of a text file or the names in a directory.
+/// Files are read up to max_bytes, at most max_lines lines are returned
+pub fn preview_lines(path: &Path, max_bytes: u64, max_lines: usize) -> Vec<String> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => return vec![format!("<{}>", e)],
+    };
+    if metadata.is_dir() {
+        return dir_preview(path, max_lines);
+    }
+    if !metadata.is_file() {
+        return vec!["<special file>".to_owned()];
+    }
+
+    let mut bytes = Vec::new();
+    let read = fs::File::open(path).and_then(|file| file.take(max_bytes).read_to_end(&mut bytes));
+    if let Err(e) = read {
+        return vec![format!("<{}>", e)];
+    }
+    //the same guess `grep` and `less` make
+    if bytes.contains(&0) {
+        return vec!["<binary file>".to_owned()];
+    }
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .take(max_lines)
+        .map(|line| line.replace('\t', "    "))
+        .collect()
+}
+
+fn dir_preview(path: &Path, max_lines: usize) -> Vec<String> {
+    let entry_iter = match fs::read_dir(path) {
+        Ok(entry_iter) => entry_iter,
+        Err(e) => return vec![format!("<{}>", e)],
+    };
+    let mut names: Vec<String> = entry_iter
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => format!("{}/", name),
+                _ => name,
+            }
+        })
+        .collect();
+    if names.is_empty() {
+        return vec!["<empty>".to_owned()];
+    }
+    names.sort();
+    names.truncate(max_lines);
+    names
+}
@@ -0,0 +1,240 @@
+use crate::event_loop::WorkerMessage;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// how many explorers the shell runs in, so prompts can show it
+pub const LEVEL_VAR: &str = "PATHFINDER_LEVEL";
+
+/// run_captured keeps this many bytes of output, then it stops the command
+pub const MAX_OUTPUT: usize = 1024 * 1024;
+
+/// an interactive $SHELL in dir, with LEVEL_VAR one higher than in the explorer's environment
+pub fn subshell(dir: &Path) -> Command {
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or("/bin/sh".to_owned());
+    let level = std::env::var(LEVEL_VAR)
+        .ok()
+        .and_then(|level| level.parse::<u32>().ok())
+        .unwrap_or(0);
+    let mut command = Command::new(shell);
+    command
+        .current_dir(dir)
+        .env(LEVEL_VAR, (level + 1).to_string());
+    command
+}
+
+/// Runs command with the terminal until it exits.
+/// Ctrl-C in the program reaches the explorer as well, so it is ignored until the program exits,
+/// the program itself gets the default handlers back
+#[cfg(unix)]
+pub fn run_attached(command: &mut Command) -> io::Result<ExitStatus> {
+    use std::os::unix::process::CommandExt;
+    //SAFETY: signal is async-signal-safe, so it may be called between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+            libc::signal(libc::SIGQUIT, libc::SIG_DFL);
+            Ok(())
+        });
+    }
+    let (old_int, old_quit) = unsafe {
+        (
+            libc::signal(libc::SIGINT, libc::SIG_IGN),
+            libc::signal(libc::SIGQUIT, libc::SIG_IGN),
+        )
+    };
+    let status = command.status();
+    unsafe {
+        libc::signal(libc::SIGINT, old_int);
+        libc::signal(libc::SIGQUIT, old_quit);
+    }
+    status
+}
+
+#[cfg(not(unix))]
+pub fn run_attached(command: &mut Command) -> io::Result<ExitStatus> {
+    command.status()
+}
+
+/// what a command run with run_captured printed
+pub struct CommandOutput {
+    /// the command line as the user typed it
+    pub command: String,
+    /// stdout and stderr in the order they were written
+    pub text: String,
+    pub status: Result<ExitStatus, String>,
+    /// the command was stopped, by the user or at MAX_OUTPUT bytes of output
+    pub truncated: bool,
+}
+
+/// Replaces `%s` with the quoted paths of selection and `%d` with the quoted dir,
+/// `%%` is a single percent sign. Paths that aren't valid UTF-8 are passed on unchanged
+pub fn expand(template: &str, selection: &[PathBuf], dir: &Path) -> OsString {
+    let mut expanded = OsString::new();
+    let mut literal = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            literal.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => {
+                expanded.push(std::mem::take(&mut literal));
+                for (i, path) in selection.iter().enumerate() {
+                    if i > 0 {
+                        expanded.push(" ");
+                    }
+                    expanded.push(quote(path));
+                }
+            }
+            Some('d') => {
+                expanded.push(std::mem::take(&mut literal));
+                expanded.push(quote(dir));
+            }
+            Some('%') => literal.push('%'),
+            Some(other) => {
+                literal.push('%');
+                literal.push(other);
+            }
+            None => literal.push('%'),
+        }
+    }
+    expanded.push(literal);
+    expanded
+}
+
+/// quotes path for sh, e.g. `it's` becomes `'it'\''s'`
+#[cfg(unix)]
+pub fn quote(path: &Path) -> OsString {
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+    let mut quoted = vec![b'\''];
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'\'' => quoted.extend_from_slice(b"'\\''"),
+            byte => quoted.push(byte),
+        }
+    }
+    quoted.push(b'\'');
+    OsString::from_vec(quoted)
+}
+
+#[cfg(not(unix))]
+pub fn quote(path: &Path) -> OsString {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''")).into()
+}
+
+/// runs script in the terminal and waits for Enter afterwards, so its output can be read
+pub fn attached(script: &OsStr, dir: &Path) -> Command {
+    let mut script = script.to_os_string();
+    script.push("\nprintf '\\n[exit %d, press enter] ' \"$?\"; read -r _");
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script).current_dir(dir);
+    command
+}
+
+/// Runs script in the background without a terminal,
+/// the output arrives as WorkerMessage::CommandOutput.
+/// Setting cancel stops the command, like reaching MAX_OUTPUT bytes of output does
+pub fn run_captured(
+    command: String,
+    script: OsString,
+    dir: PathBuf,
+    cancel: Arc<AtomicBool>,
+    sender: Sender<WorkerMessage>,
+) {
+    thread::spawn(move || {
+        let output = match capture(&script, &dir, &cancel) {
+            Ok((bytes, status, truncated)) => CommandOutput {
+                command,
+                text: String::from_utf8_lossy(&bytes).into_owned(),
+                status: Ok(status),
+                truncated,
+            },
+            Err(e) => CommandOutput {
+                command,
+                text: String::new(),
+                status: Err(e.to_string()),
+                truncated: false,
+            },
+        };
+        let _ = sender.send(WorkerMessage::CommandOutput(output));
+    });
+}
+
+/// runs script and collects its output until it exits, is cancelled or printed too much
+fn capture(
+    script: &OsStr,
+    dir: &Path,
+    cancel: &AtomicBool,
+) -> io::Result<(Vec<u8>, ExitStatus, bool)> {
+    //the group keeps stdout and stderr in order, like in a terminal
+    let mut group = OsString::from("{ ");
+    group.push(script);
+    group.push("\n} 2>&1");
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(group)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped());
+    //its own process group, so stopping it stops the programs it started as well
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = command.spawn()?;
+
+    //reading blocks, so it happens on another thread while this one watches cancel
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let (chunk_sender, chunks) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match stdout.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if chunk_sender.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+    while !truncated {
+        match chunks.recv_timeout(Duration::from_millis(50)) {
+            Ok(chunk) => bytes.extend_from_slice(&chunk),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if bytes.len() >= MAX_OUTPUT {
+            bytes.truncate(MAX_OUTPUT);
+            truncated = true;
+        }
+        truncated |= cancel.load(Ordering::Relaxed);
+    }
+    if truncated {
+        stop(&mut child);
+    }
+    Ok((bytes, child.wait()?, truncated))
+}
+
+#[cfg(unix)]
+fn stop(child: &mut Child) {
+    unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+}
+
+#[cfg(not(unix))]
+fn stop(child: &mut Child) {
+    let _ = child.kill();
+}
@@ -0,0 +1,221 @@
+use crate::entry::{Entry, EntryType};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// the property entries are sorted by
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SortKey {
+    Size,
+    Name,
+    NameCaseInsensitive,
+    Natural,
+    Modified,
+    Changed,
+    Extension,
+    Type,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Sorting {
+    Unsorted,
+    Ascending(SortKey),
+    Descending(SortKey),
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 8] = [
+        SortKey::Size,
+        SortKey::Name,
+        SortKey::NameCaseInsensitive,
+        SortKey::Natural,
+        SortKey::Modified,
+        SortKey::Changed,
+        SortKey::Extension,
+        SortKey::Type,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Size => "Size",
+            SortKey::Name => "Name",
+            SortKey::NameCaseInsensitive => "Name (ignore case)",
+            SortKey::Natural => "Natural",
+            SortKey::Modified => "Modified",
+            SortKey::Changed => "Changed",
+            SortKey::Extension => "Extension",
+            SortKey::Type => "Type",
+        }
+    }
+
+    /// the name used in config files and commands
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Size => "size",
+            SortKey::Name => "name",
+            SortKey::NameCaseInsensitive => "iname",
+            SortKey::Natural => "natural",
+            SortKey::Modified => "mtime",
+            SortKey::Changed => "ctime",
+            SortKey::Extension => "extension",
+            SortKey::Type => "type",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "modified" => Some(SortKey::Modified),
+            "changed" => Some(SortKey::Changed),
+            "ext" => Some(SortKey::Extension),
+            _ => SortKey::ALL.into_iter().find(|key| key.name() == name),
+        }
+    }
+}
+
+impl Sorting {
+    pub fn label(&self) -> String {
+        match self {
+            Sorting::Unsorted => "Unsorted".to_owned(),
+            Sorting::Ascending(key) => format!("{}↑", key.label()),
+            Sorting::Descending(key) => format!("{}↓", key.label()),
+        }
+    }
+
+    /// every sort key in both directions
+    pub fn modes() -> impl Iterator<Item = Sorting> {
+        SortKey::ALL
+            .into_iter()
+            .flat_map(|key| [Sorting::Descending(key), Sorting::Ascending(key)])
+    }
+
+    /// the text parse turns back into this sorting
+    pub fn name(&self) -> String {
+        match self {
+            Sorting::Unsorted => "none".to_owned(),
+            Sorting::Ascending(key) => key.name().to_owned(),
+            Sorting::Descending(key) => format!("{} desc", key.name()),
+        }
+    }
+
+    /// parses a sort key with an optional direction, e.g. `name`, `mtime desc` or `none`
+    pub fn parse(text: &str) -> Result<Sorting, String> {
+        let mut words = text.split_whitespace();
+        let key = match words.next() {
+            Some("none") => return Ok(Sorting::Unsorted),
+            Some(name) => match SortKey::from_name(name) {
+                Some(key) => key,
+                None => return Err(format!("unknown sort key \"{}\"", name)),
+            },
+            None => return Err("missing sort key".to_owned()),
+        };
+        let sorting = match words.next() {
+            None | Some("asc") | Some("ascending") => Sorting::Ascending(key),
+            Some("desc") | Some("descending") => Sorting::Descending(key),
+            Some(order) => return Err(format!("unknown sort order \"{}\"", order)),
+        };
+        match words.next() {
+            None => Ok(sorting),
+            Some(word) => Err(format!("unexpected \"{}\" after the sort order", word)),
+        }
+    }
+}
+
+/// everything besides the entries themselves that is needed to compare them
+pub struct SortContext<'a> {
+    pub dir_sizes: &'a HashMap<PathBuf, u64>,
+}
+
+impl SortContext<'_> {
+    /// compares two entries by key in ascending order, ties are broken by name
+    pub fn compare(&self, key: SortKey, a: &Entry, b: &Entry) -> Ordering {
+        let ordering = match key {
+            SortKey::Size => self.size(a).cmp(&self.size(b)),
+            SortKey::Name => a.name.cmp(&b.name),
+            SortKey::NameCaseInsensitive => a
+                .name
+                .to_string_lossy()
+                .to_lowercase()
+                .cmp(&b.name.to_string_lossy().to_lowercase()),
+            SortKey::Natural => natural_cmp(&a.name.to_string_lossy(), &b.name.to_string_lossy()),
+            SortKey::Modified => a.modified.cmp(&b.modified),
+            SortKey::Changed => a.changed.cmp(&b.changed),
+            SortKey::Extension => extension(a).cmp(&extension(b)),
+            SortKey::Type => type_rank(a).cmp(&type_rank(b)),
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    }
+
+    ///size of a file, or the cached recursive size of a directory (0 if not calculated yet)
+    fn size(&self, entry: &Entry) -> u64 {
+        if entry.is_dir() {
+            *self.dir_sizes.get(&entry.path).unwrap_or(&0)
+        } else {
+            entry.size
+        }
+    }
+}
+
+fn extension(entry: &Entry) -> Option<String> {
+    Path::new(&entry.name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+}
+
+/// directories first, then symlinks, regular files and everything else
+fn type_rank(entry: &Entry) -> u8 {
+    match entry.file_type {
+        EntryType::Dir => 0,
+        EntryType::Symlink => 1,
+        EntryType::File => 2,
+        _ => 3,
+    }
+}
+
+/// compares strings like `ls -v` does, runs of digits are compared by their numeric value.
+/// So `file2` comes before `file10`. Leading zeros only break ties
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    let mut zeros_tie = Ordering::Equal;
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return zeros_tie,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_digits(&mut a_chars);
+                let y_digits = take_digits(&mut b_chars);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                zeros_tie = zeros_tie.then(x_digits.len().cmp(&y_digits.len()));
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(*c);
+        chars.next();
+    }
+    digits
+}
@@ -0,0 +1,1360 @@
+#[test]
+fn test_file_manager() {}
+
+#[test]
+fn test_format_permissions() {
+    use crate::util::format_permissions;
+    assert_eq!(format_permissions(0o040755), "drwxr-xr-x");
+    assert_eq!(format_permissions(0o100644), "-rw-r--r--");
+    assert_eq!(format_permissions(0o120777), "lrwxrwxrwx");
+    assert_eq!(format_permissions(0o104755), "-rwsr-xr-x");
+    assert_eq!(format_permissions(0o041777), "drwxrwxrwt");
+    assert_eq!(format_permissions(0o102644), "-rw-r-Sr--");
+}
+
+/// creates a fresh, empty directory below the system temp dir
+#[cfg(test)]
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("pathfinder_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// a FileManager whose worker messages arrive at the returned receiver instead of an event loop
+#[cfg(test)]
+fn new_file_manager() -> (
+    crate::file_manager::FileManager,
+    std::sync::mpsc::Receiver<crate::event_loop::WorkerMessage>,
+) {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut file_manager =
+        crate::file_manager::FileManager::new(sender, &crate::config::Config::default());
+    //tests that trash something set up their own trash, not the one of the user
+    file_manager.trash = None;
+    (file_manager, receiver)
+}
+
+/// handles worker messages until the current directory is loaded completely
+#[cfg(test)]
+fn wait_for_load(
+    file_manager: &mut crate::file_manager::FileManager,
+    receiver: &std::sync::mpsc::Receiver<crate::event_loop::WorkerMessage>,
+) {
+    while file_manager.is_loading() {
+        let message = receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("directory load got stuck");
+        file_manager.handle_worker_message(message);
+    }
+    file_manager.sort_if_changed();
+}
+
+#[test]
+fn test_format_size() {
+    use crate::util::{SizeFormat, format_size};
+    assert_eq!(format_size(1536, SizeFormat::Bytes), "1536");
+    assert_eq!(format_size(512, SizeFormat::Iec), "512 B");
+    assert_eq!(format_size(1536, SizeFormat::Iec), "1.5 KiB");
+    assert_eq!(format_size(1500, SizeFormat::Si), "1.5 kB");
+    assert_eq!(
+        format_size(3 * 1024 * 1024 * 1024, SizeFormat::Iec),
+        "3.0 GiB"
+    );
+}
+
+#[test]
+fn test_dir_size() {
+    let dir = temp_dir("dir_size");
+    std::fs::create_dir_all(dir.join("a/b")).unwrap();
+    std::fs::write(dir.join("x"), [0u8; 100]).unwrap();
+    std::fs::write(dir.join("a/y"), [0u8; 20]).unwrap();
+    std::fs::write(dir.join("a/b/z"), [0u8; 3]).unwrap();
+    assert_eq!(crate::dir_size::dir_size(&dir), 123);
+    //hard links count once
+    std::fs::hard_link(dir.join("x"), dir.join("a/b/x")).unwrap();
+    assert_eq!(crate::dir_size::dir_size(&dir), 123);
+    assert_eq!(crate::dir_size::dir_size(&dir.join("a")), 123);
+    //every level is walked, not only the top one
+    let mut deep = dir.join("deep");
+    for i in 0..20 {
+        deep = deep.join(i.to_string());
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(deep.join("f"), [0u8; 1]).unwrap();
+    }
+    assert_eq!(crate::dir_size::dir_size(&dir), 143);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_disk_usage_scan() {
+    use std::ffi::OsString;
+    let dir = temp_dir("disk_usage");
+    std::fs::create_dir_all(dir.join("big/inner")).unwrap();
+    std::fs::create_dir_all(dir.join("small")).unwrap();
+    std::fs::write(dir.join("big/inner/file"), [0u8; 300]).unwrap();
+    std::fs::write(dir.join("small/file"), [0u8; 10]).unwrap();
+    std::fs::write(dir.join("top"), [0u8; 50]).unwrap();
+
+    std::fs::hard_link(dir.join("top"), dir.join("small/top")).unwrap();
+
+    let mut node = crate::disk_usage::scan(&dir);
+    assert_eq!(node.size, 360);
+    let names: Vec<_> = node.children.iter().map(|c| c.name.clone()).collect();
+    assert_eq!(names, ["big", "top", "small"]);
+
+    let path = [OsString::from("big"), OsString::from("inner")];
+    assert_eq!(node.remove(&path), Some(300));
+    assert_eq!(node.size, 60);
+    assert_eq!(node.get(&path[..1]).unwrap().size, 0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_natural_cmp() {
+    use crate::sorting::natural_cmp;
+    let mut names = vec!["file10", "file2", "file1", "a", "file02", "file2b", "b1"];
+    names.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(
+        names,
+        ["a", "b1", "file1", "file2", "file02", "file2b", "file10"]
+    );
+}
+
+/// tests that use a FileManager change the working directory of the whole process,
+/// so they must not run in parallel
+#[cfg(test)]
+static CWD_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(all(test, unix))]
+#[test]
+fn test_non_utf8_names() {
+    use crate::keymap::Action;
+    use crate::sorting::{SortKey, Sorting};
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::message::{Message, MessageSender};
+    use crate::ui::windows::explorer_table::ExplorerTable;
+    use std::os::unix::ffi::OsStrExt;
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("non_utf8");
+    let name = std::ffi::OsStr::from_bytes(b"bad\xff\xfe.txt");
+    std::fs::write(dir.join(name), "x").unwrap();
+    std::fs::write(dir.join("good.txt"), "y").unwrap();
+    std::fs::create_dir(dir.join("folder")).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    assert_eq!(file_manager.num_files, 3);
+    for key in SortKey::ALL {
+        file_manager.sort(Sorting::Ascending(key));
+        file_manager.sort(Sorting::Descending(key));
+    }
+    file_manager.sort(Sorting::Ascending(SortKey::Name));
+    let names: Vec<_> = file_manager
+        .get_entries()
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    assert_eq!(names[0], name);
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 10)).unwrap();
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    terminal
+        .draw(|frame| explorer_table.draw(frame, &mut file_manager))
+        .unwrap();
+    //the open with popup gets the file name as it is
+    assert!(matches!(
+        explorer_table.run_action(Action::OpenWith, &mut file_manager),
+        AppEvents::OpenOpenWithPopup
+    ));
+    match explorer_table.get_message() {
+        Some(Message::Path(path)) => assert_eq!(path.file_name(), Some(name)),
+        _ => panic!("open with didn't get the path"),
+    }
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// run with `cargo test --release -- --ignored --nocapture bench_huge_directory`
+#[cfg(test)]
+#[test]
+#[ignore]
+fn bench_huge_directory() {
+    use crate::ui::controller::State;
+    use crate::ui::windows::explorer_table::ExplorerTable;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::time::Instant;
+
+    const NUM_FILES: usize = 500_000;
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("huge_directory");
+    for i in 0..NUM_FILES {
+        std::fs::File::create(dir.join(format!("file{}", i))).unwrap();
+    }
+    std::env::set_current_dir(&dir).unwrap();
+
+    let start = Instant::now();
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    println!("loading {} entries: {:?}", NUM_FILES, start.elapsed());
+    assert_eq!(file_manager.num_files, NUM_FILES);
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 50)).unwrap();
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    let keys = [
+        KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL),
+        KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE),
+        KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE),
+    ];
+    let frames = 1000;
+    let start = Instant::now();
+    for i in 0..frames {
+        explorer_table.handle_key_event(keys[i % keys.len()], &mut file_manager);
+        terminal
+            .draw(|frame| explorer_table.draw(frame, &mut file_manager))
+            .unwrap();
+    }
+    let per_frame = start.elapsed() / frames as u32;
+    println!("scrolling: {:?} per frame", per_frame);
+    assert!(per_frame.as_millis() < 16, "scrolling is not smooth");
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_async_dir_load() {
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("async_load");
+    std::fs::create_dir_all(dir.join("a")).unwrap();
+    std::fs::create_dir_all(dir.join("b")).unwrap();
+    for i in 0..2000 {
+        std::fs::write(dir.join("a").join(format!("{}", i)), "").unwrap();
+    }
+    std::fs::write(dir.join("b/only"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    assert_eq!(file_manager.num_files, 2);
+
+    //navigating away cancels the load of a
+    file_manager.change_dir("a".into()).unwrap();
+    file_manager.change_dir("../b".into()).unwrap();
+    wait_for_load(&mut file_manager, &receiver);
+    assert!(!file_manager.is_loading());
+    assert_eq!(file_manager.num_files, 1);
+    assert_eq!(file_manager.get_entries()[0].name, "only");
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_disk_usage_delete_while_scanning() {
+    use crate::error::Error;
+    use std::ffi::OsString;
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("disk_usage_delete");
+    std::fs::write(dir.join("big"), [0u8; 100]).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    let finish_scan = |file_manager: &mut crate::file_manager::FileManager| {
+        while file_manager.is_disk_usage_scanning() {
+            let message = receiver
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .expect("disk usage scan got stuck");
+            file_manager.handle_worker_message(message);
+        }
+    };
+    file_manager.scan_disk_usage();
+    finish_scan(&mut file_manager);
+
+    //the tree of the last scan may be outdated while a new one runs
+    file_manager.scan_disk_usage();
+    let big = [OsString::from("big")];
+    assert!(matches!(
+        file_manager.delete_from_disk_usage(&big),
+        Err(Error::Refused(_))
+    ));
+    assert!(dir.join("big").exists());
+    finish_scan(&mut file_manager);
+    file_manager.delete_from_disk_usage(&big).unwrap();
+    assert!(!dir.join("big").exists());
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_paste_onto_itself() {
+    use crate::error::Error;
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("paste_onto_itself");
+    std::fs::create_dir_all(dir.join("folder/inner")).unwrap();
+    std::fs::write(dir.join("folder/data"), "data").unwrap();
+    std::fs::write(dir.join("file"), "file").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    //pasting into the folder the files are in already leaves them alone
+    file_manager.add_to_selection(dir.join("file")).unwrap();
+    file_manager.add_to_selection(dir.join("folder")).unwrap();
+    assert!(matches!(file_manager.paste(), Err(Error::Refused(_))));
+    assert_eq!(std::fs::read_to_string(dir.join("file")).unwrap(), "file");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("folder/data")).unwrap(),
+        "data"
+    );
+
+    //a folder can't be pasted into itself
+    file_manager.clear_selection();
+    file_manager.add_to_selection(dir.join("folder")).unwrap();
+    file_manager.change_dir(dir.join("folder/inner")).unwrap();
+    assert!(matches!(file_manager.paste(), Err(Error::Refused(_))));
+    assert!(!dir.join("folder/inner/folder").exists());
+
+    //elsewhere it is copied
+    file_manager.add_to_selection(dir.join("file")).unwrap();
+    file_manager.paste().unwrap_err();
+    file_manager
+        .remove_from_selection(dir.join("folder"))
+        .unwrap();
+    file_manager.paste().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("folder/inner/file")).unwrap(),
+        "file"
+    );
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_dir_sizes_invalidated() {
+    use crate::event_loop::WorkerMessage;
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("dir_sizes_invalidated");
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(dir.join("sub/file"), [0u8; 10]).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    let sub = file_manager.get_entries()[0].path.clone();
+    //handles worker messages until a calculation or scan finished
+    let finish = |file_manager: &mut crate::file_manager::FileManager| loop {
+        let message = receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("size calculation got stuck");
+        let done = matches!(
+            message,
+            WorkerMessage::DirSize(..) | WorkerMessage::DiskUsage(..)
+        );
+        file_manager.handle_worker_message(message);
+        if done {
+            break;
+        }
+    };
+    file_manager.calculate_dir_sizes();
+    finish(&mut file_manager);
+    assert_eq!(file_manager.dir_size(&sub), Some(10));
+
+    //changes next to the folder keep its size
+    file_manager.create_file(dir.join("other")).unwrap();
+    file_manager.handle_worker_message(WorkerMessage::DirChanged(vec![dir.join("other")]));
+    assert_eq!(file_manager.dir_size(&sub), Some(10));
+    //changes in it forget the size
+    file_manager.create_file(dir.join("sub/new")).unwrap();
+    assert_eq!(file_manager.dir_size(&sub), None);
+    file_manager.calculate_dir_sizes();
+    finish(&mut file_manager);
+    assert_eq!(file_manager.dir_size(&sub), Some(10));
+    file_manager
+        .rename(&dir.join("sub/new"), &dir.join("new"))
+        .unwrap();
+    assert_eq!(file_manager.dir_size(&sub), None);
+
+    //a calculation goes on when something else changes
+    file_manager.calculate_dir_sizes();
+    file_manager.create_file(dir.join("another")).unwrap();
+    finish(&mut file_manager);
+    assert_eq!(file_manager.dir_size(&sub), Some(10));
+
+    //but a size calculated before the folder changed is dropped
+    file_manager.handle_worker_message(WorkerMessage::DirChanged(vec![sub.clone()]));
+    file_manager.calculate_dir_sizes();
+    file_manager.create_file(dir.join("sub/late")).unwrap();
+    finish(&mut file_manager);
+    assert_eq!(file_manager.dir_size(&sub), None);
+    assert!(!file_manager.is_dir_size_pending(&sub));
+    //the same for the sizes of a disk usage scan
+    file_manager.scan_disk_usage();
+    file_manager.create_file(dir.join("sub/later")).unwrap();
+    finish(&mut file_manager);
+    assert!(file_manager.disk_usage().is_some());
+    assert_eq!(file_manager.dir_size(&sub), None);
+    file_manager.scan_disk_usage();
+    finish(&mut file_manager);
+    assert_eq!(file_manager.dir_size(&sub), Some(10));
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_watcher_refreshes_listing() {
+    use crate::event_loop::WorkerMessage;
+    use std::time::Duration;
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("watcher");
+    std::env::set_current_dir(&dir).unwrap();
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    assert_eq!(file_manager.num_files, 0);
+
+    //created by "another shell"
+    std::fs::write(dir.join("external"), "").unwrap();
+    loop {
+        let message = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no refresh");
+        let changed = match &message {
+            WorkerMessage::DirChanged(paths) => {
+                assert!(paths.contains(&file_manager.current_dir().join("external")));
+                true
+            }
+            _ => false,
+        };
+        file_manager.handle_worker_message(message);
+        if changed {
+            break;
+        }
+    }
+    wait_for_load(&mut file_manager, &receiver);
+    assert_eq!(file_manager.num_files, 1);
+
+    //previews are read in the background and dropped when the previewed folder changes
+    std::fs::create_dir(dir.join("sub")).unwrap();
+    let sub = dir.join("sub");
+    //a load is dropped if the watcher reports a change of sub, e.g. its creation, meanwhile
+    let load_preview = |file_manager: &mut crate::file_manager::FileManager| {
+        while file_manager.preview(&sub).is_none() {
+            file_manager.load_preview(&sub, 1024, 10);
+            loop {
+                let message = receiver
+                    .recv_timeout(Duration::from_secs(5))
+                    .expect("no preview");
+                let loaded = matches!(message, WorkerMessage::Preview(..));
+                file_manager.handle_worker_message(message);
+                if loaded {
+                    break;
+                }
+            }
+        }
+    };
+    load_preview(&mut file_manager);
+    assert_eq!(file_manager.preview(&sub).unwrap().lines, ["<empty>"]);
+    std::fs::write(dir.join("sub/inner"), "").unwrap();
+    loop {
+        let message = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no preview refresh");
+        let changed = matches!(message, WorkerMessage::PreviewChanged(_));
+        file_manager.handle_worker_message(message);
+        if changed {
+            break;
+        }
+    }
+    assert!(file_manager.preview(&sub).is_none());
+    load_preview(&mut file_manager);
+    assert_eq!(file_manager.preview(&sub).unwrap().lines, ["inner"]);
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_mouse_in_explorer() {
+    use crate::sorting::{SortKey, Sorting};
+    use crate::ui::controller::State;
+    use crate::ui::windows::explorer_table::ExplorerTable;
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    let click = |column, row| MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("mouse");
+    std::fs::create_dir(dir.join("a")).unwrap();
+    std::fs::write(dir.join("b"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 10)).unwrap();
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    terminal
+        .draw(|frame| explorer_table.draw(frame, &mut file_manager))
+        .unwrap();
+
+    //header line below the border
+    explorer_table.handle_mouse_event(click(2, 1), &mut file_manager);
+    assert!(file_manager.sorting() == Sorting::Ascending(SortKey::Name));
+    explorer_table.handle_mouse_event(click(2, 1), &mut file_manager);
+    assert!(file_manager.sorting() == Sorting::Descending(SortKey::Name));
+
+    //"a" is the second row now, a double click enters it
+    explorer_table.handle_mouse_event(click(2, 3), &mut file_manager);
+    explorer_table.handle_mouse_event(click(2, 3), &mut file_manager);
+    wait_for_load(&mut file_manager, &receiver);
+    assert_eq!(file_manager.current_dir(), dir.join("a"));
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_keymap() {
+    use crate::keymap::{Action, KeyChord, KeyMap, KeyMatch};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let chord = |code| KeyChord::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        KeyChord::parse("C-S-p"),
+        Ok(KeyChord::new(
+            KeyCode::Char('p'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        ))
+    );
+    assert_eq!(
+        KeyChord::parse("G"),
+        Ok(KeyChord::new(KeyCode::Char('g'), KeyModifiers::SHIFT))
+    );
+    assert_eq!(KeyChord::parse("-"), Ok(chord(KeyCode::Char('-'))));
+    assert!(KeyChord::parse("hyper-x").is_err());
+
+    let mut keymap = KeyMap::default();
+    let g = chord(KeyCode::Char('g'));
+    assert!(matches!(keymap.lookup(&[g]), KeyMatch::Prefix));
+    assert!(matches!(
+        keymap.lookup(&[g, g]),
+        KeyMatch::Action(Action::First)
+    ));
+    assert!(matches!(
+        keymap.lookup(&[chord(KeyCode::Enter)]),
+        KeyMatch::Action(Action::EnterDir)
+    ));
+
+    keymap
+        .merge_toml("\"C-n\" = \"move_down\"\n\"x\" = \"none\"\n\"g h\" = \"parent_dir\"")
+        .unwrap();
+    let ctrl_n = KeyChord::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+    assert!(matches!(
+        keymap.lookup(&[ctrl_n]),
+        KeyMatch::Action(Action::MoveDown)
+    ));
+    assert!(matches!(
+        keymap.lookup(&[chord(KeyCode::Char('x'))]),
+        KeyMatch::None
+    ));
+    assert!(keymap.keys_for(Action::Delete).is_empty());
+    assert_eq!(
+        keymap.keys_for(Action::ParentDir),
+        ["<h>", "<Left>", "<g h>"]
+    );
+    assert!(keymap.merge_toml("\"q\" = \"explode\"").is_err());
+    assert!(keymap.merge_toml("q = ").is_err());
+}
+
+#[test]
+fn test_config() {
+    use crate::column::Column;
+    use crate::config::Config;
+    use crate::file_manager::SortDir;
+    use crate::sorting::{SortKey, Sorting};
+
+    let config = Config::parse(
+        r#"
+        sorting = "mtime desc"
+        dir_sorting = "start"
+        columns = ["size", "owner"]
+        [confirm]
+        delete = false
+        "#,
+    )
+    .unwrap();
+    assert!(config.sorting == Sorting::Descending(SortKey::Modified));
+    assert!(config.dir_sorting == SortDir::Start);
+    assert!(config.columns == [Column::Name, Column::Size, Column::Owner]);
+    assert!(!config.confirm.delete);
+    assert!(config.confirm.overwrite);
+    assert!(!config.show_hidden);
+
+    let error = Config::parse("sorting = \"sideways\"").err().unwrap();
+    assert!(error.contains("unknown sort key \"sideways\""), "{}", error);
+    let error = Config::parse("columns = [\"size\", \"size\"]")
+        .err()
+        .unwrap();
+    assert!(
+        error.contains("column \"size\" is listed twice"),
+        "{}",
+        error
+    );
+    assert!(Config::parse("show_hidden = \"yes\"").is_err());
+    assert!(Config::parse("colour = true").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_theme() {
+    use crate::entry::Entry;
+    use crate::theme::{LsColors, Theme, parse_style};
+    use ratatui::style::{Color, Modifier, Style};
+    use std::os::unix::fs::PermissionsExt;
+
+    assert_eq!(
+        parse_style("bold black on #ffaf00"),
+        Ok(Style::new()
+            .fg(Color::Black)
+            .bg(Color::Rgb(0xff, 0xaf, 0x00))
+            .add_modifier(Modifier::BOLD))
+    );
+    assert!(parse_style("blue on").is_err());
+    assert!(parse_style("sparkly").is_err());
+
+    let dir = temp_dir("theme");
+    std::fs::write(dir.join("Backup.TAR.GZ"), "").unwrap();
+    std::fs::write(dir.join("notes.txt"), "").unwrap();
+    std::fs::write(dir.join("run"), "").unwrap();
+    std::fs::set_permissions(dir.join("run"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::os::unix::fs::symlink("run", dir.join("link")).unwrap();
+    std::os::unix::fs::symlink("missing", dir.join("broken")).unwrap();
+    let entry = |name: &str| {
+        let dir_entry = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .find(|e| e.file_name() == name)
+            .unwrap();
+        Entry::from_dir_entry(&dir_entry, &dir)
+    };
+
+    let ls_colors = LsColors::parse("di=01;34:ex=01;32:*.tar=01;31:*.tar.gz=38;5;208:bad=x;y");
+    assert_eq!(
+        ls_colors.style(&entry("Backup.TAR.GZ")),
+        Some(Style::new().fg(Color::Indexed(208)))
+    );
+    assert_eq!(ls_colors.style(&entry("notes.txt")), None);
+    assert_eq!(
+        ls_colors.style(&entry("run")),
+        Some(
+            Style::new()
+                .fg(Color::Indexed(2))
+                .add_modifier(Modifier::BOLD)
+        )
+    );
+    assert_eq!(
+        Theme::dark().entry_style(&entry("run")),
+        Theme::dark().executable
+    );
+    assert!(entry("broken").broken_link && !entry("link").broken_link);
+    let ls_colors = LsColors::parse("ln=36:or=31");
+    assert_eq!(
+        ls_colors.style(&entry("broken")),
+        Some(Style::new().fg(Color::Indexed(1)))
+    );
+    assert_eq!(
+        ls_colors.style(&entry("link")),
+        Some(Style::new().fg(Color::Indexed(6)))
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_command_palette() {
+    use crate::keymap::Action;
+    use crate::sorting::{SortKey, Sorting};
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::message::{Message, MessageSender};
+    use crate::ui::windows::popups::command_palette_popup::CommandPalettePopup;
+    use crate::util::fuzzy_score;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    assert!(fuzzy_score("hdn", "toggle hidden files").is_some());
+    assert!(fuzzy_score("ndh", "toggle hidden files").is_none());
+    assert!(fuzzy_score("paste", "paste selection") > fuzzy_score("paste", "p a s t e"));
+    for action in Action::all() {
+        assert_eq!(Action::from_name(&action.name()), Some(action));
+    }
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (mut file_manager, _receiver) = new_file_manager();
+    let mut palette = CommandPalettePopup::new(Default::default());
+    for c in "sort mtime desc".chars() {
+        let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        palette.handle_key_event(key, &mut file_manager);
+    }
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(matches!(
+        palette.handle_key_event(enter, &mut file_manager),
+        AppEvents::ClosePopUp
+    ));
+    assert!(matches!(
+        palette.get_message(),
+        Some(Message::Action(Action::SortBy(Sorting::Descending(
+            SortKey::Modified
+        ))))
+    ));
+}
+
+#[test]
+fn test_command_line() {
+    use crate::command::{self, Command, CommandHistory};
+    use crate::sorting::{SortKey, Sorting};
+    use crate::ui::controller::State;
+    use crate::ui::windows::explorer_table::ExplorerTable;
+    use crate::util::glob_match;
+    use std::path::PathBuf;
+
+    assert_eq!(
+        Command::parse("sort mtime desc"),
+        Ok(Command::Sort(Sorting::Descending(SortKey::Modified)))
+    );
+    assert_eq!(
+        Command::parse("rename 'old name' new\\ name"),
+        Ok(Command::Rename(
+            Some(PathBuf::from("old name")),
+            PathBuf::from("new name")
+        ))
+    );
+    assert_eq!(Command::parse("set hidden!"), Ok(Command::SetHidden(None)));
+    assert!(Command::parse("mkdir").is_err());
+    assert!(Command::parse("frobnicate").is_err());
+
+    assert!(glob_match("*.log", "app.log"));
+    assert!(!glob_match("*.log", "app.log.1"));
+    assert!(glob_match("a?c[0-9]*", "abc7.txt"));
+    assert!(!glob_match("[!a]*", "abc"));
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("command_line");
+    std::fs::create_dir(dir.join("src")).unwrap();
+    std::fs::write(dir.join("a.log"), "").unwrap();
+    std::fs::write(dir.join("b.log"), "").unwrap();
+    std::fs::write(dir.join("notes"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    assert_eq!(command::complete("so"), (0, vec!["sort".to_owned()]));
+    assert_eq!(command::complete("cd "), (3, vec!["src/".to_owned()]));
+    assert_eq!(command::complete("touch no"), (6, vec!["notes".to_owned()]));
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    let mut run = |line: &str, file_manager: &mut crate::file_manager::FileManager| {
+        explorer_table.run_command(Command::parse(line).unwrap(), file_manager);
+        wait_for_load(file_manager, &receiver);
+    };
+    run("mkdir x/y", &mut file_manager);
+    assert!(dir.join("x/y").is_dir());
+    run("rename notes todo", &mut file_manager);
+    assert!(dir.join("todo").exists());
+    run("select *.log", &mut file_manager);
+    assert!(file_manager.is_selected(&dir.join("a.log")));
+    assert!(!file_manager.is_selected(&dir.join("todo")));
+
+    let history_path = dir.join("state/history");
+    let mut history = CommandHistory::load_from(Some(history_path.clone()));
+    history.push("cd src");
+    history.push("sort name");
+    history.push("cd src");
+    history.save().unwrap();
+    let history = CommandHistory::load_from(Some(history_path));
+    assert_eq!(history.entries(), ["sort name", "cd src"]);
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_opener() {
+    use crate::config::Config;
+    use crate::opener::{self, Launch};
+    use std::path::{Path, PathBuf};
+
+    let args = |command: &std::process::Command| -> Vec<String> {
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    };
+    let files = [PathBuf::from("a b.txt"), PathBuf::from("c.txt")];
+    let command = opener::build_command("diff -u %F", &files[0], &files).unwrap();
+    assert_eq!(opener::program_name(&command), "diff");
+    assert_eq!(args(&command), ["-u", "a b.txt", "c.txt"]);
+    let command = opener::build_command("mpv --title='%f'", &files[0], &files).unwrap();
+    assert_eq!(args(&command), ["--title=a b.txt"]);
+    let command = opener::build_command("less", &files[0], &files).unwrap();
+    assert_eq!(args(&command), ["a b.txt"]);
+
+    assert_eq!(opener::mime_type(Path::new("x.PNG")), "image/png");
+
+    let config = Config::parse(
+        r#"
+        editor = "vi -R"
+        opener = "my-open"
+        [[open]]
+        mime = "image/*"
+        command = "feh %f"
+        [[open]]
+        ext = ["md"]
+        command = "glow"
+        terminal = true
+        "#,
+    )
+    .unwrap();
+    let launch = |name: &str| opener::launch_for(Path::new(name), &config).unwrap();
+    assert!(matches!(launch("cat.jpg"), Launch::Detached(c) if opener::program_name(&c) == "feh"));
+    assert!(
+        matches!(launch("README.MD"), Launch::Terminal(c) if opener::program_name(&c) == "glow")
+    );
+    assert!(matches!(launch("main.rs"), Launch::Terminal(c) if args(&c) == ["-R", "main.rs"]));
+    assert!(
+        matches!(launch("song.mp3"), Launch::Detached(c) if opener::program_name(&c) == "my-open")
+    );
+}
+
+#[test]
+fn test_open_with() {
+    use crate::command::Command;
+    use crate::config::Config;
+    use crate::opener;
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::message::{Message, MessageSender};
+    use crate::ui::windows::popups::open_with_popup::OpenWithPopup;
+    use crossterm::event::{KeyCode, KeyEvent};
+    use std::path::PathBuf;
+
+    let dir = temp_dir("open_with");
+    let local = dir.join("local");
+    let system = dir.join("system");
+    std::fs::create_dir_all(&local).unwrap();
+    std::fs::create_dir_all(&system).unwrap();
+    let desktop = |name: &str, mime: &str, extra: &str| {
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nName[de]=Anders\nExec={} %U\nMimeType={};\n{}\n[Desktop Action new]\nExec=ignored\n",
+            name,
+            name.to_lowercase(),
+            mime,
+            extra
+        )
+    };
+    std::fs::write(
+        local.join("viewer.desktop"),
+        desktop("Viewer", "image/png", ""),
+    )
+    .unwrap();
+    //shadowed by the local file with the same name
+    std::fs::write(
+        system.join("viewer.desktop"),
+        desktop("Old", "image/png", ""),
+    )
+    .unwrap();
+    std::fs::write(
+        system.join("paint.desktop"),
+        desktop("Paint", "image/*", "Terminal=true"),
+    )
+    .unwrap();
+    std::fs::write(
+        system.join("hidden.desktop"),
+        desktop("Hidden", "image/png", "NoDisplay=true"),
+    )
+    .unwrap();
+    std::fs::write(
+        system.join("player.desktop"),
+        desktop("Player", "audio/mpeg", ""),
+    )
+    .unwrap();
+
+    let config =
+        Config::parse("opener = \"my-open\"\n[[open]]\next = [\"png\"]\ncommand = \"feh\"")
+            .unwrap();
+    let names: Vec<(String, String, bool)> =
+        opener::candidates(&PathBuf::from("cat.png"), &config, &[local, system])
+            .into_iter()
+            .map(|c| (c.name, c.command, c.terminal))
+            .filter(|(name, _, _)| !name.starts_with("editor"))
+            .collect();
+    let owned =
+        |name: &str, command: &str, terminal| (name.to_owned(), command.to_owned(), terminal);
+    assert_eq!(
+        names,
+        [
+            owned("feh", "feh", false),
+            owned("Viewer", "viewer %F", false),
+            owned("Paint", "paint %F", true),
+            owned("my-open", "my-open", false),
+        ]
+    );
+
+    let (mut file_manager, _receiver) = new_file_manager();
+    let key = |code| KeyEvent::from(code);
+    let candidates = vec![opener::Candidate {
+        name: "Viewer".to_owned(),
+        command: "viewer %F".to_owned(),
+        terminal: false,
+    }];
+    let mut popup = OpenWithPopup::with_candidates(PathBuf::from("cat.png"), candidates);
+    //the item after the candidates asks for a command
+    popup.handle_key_event(key(KeyCode::Down), &mut file_manager);
+    popup.handle_key_event(key(KeyCode::Enter), &mut file_manager);
+    for c in "gimp %f".chars() {
+        popup.handle_key_event(key(KeyCode::Char(c)), &mut file_manager);
+    }
+    popup.handle_key_event(key(KeyCode::Tab), &mut file_manager);
+    assert!(matches!(
+        popup.handle_key_event(key(KeyCode::Enter), &mut file_manager),
+        AppEvents::ClosePopUp
+    ));
+    assert!(matches!(
+        popup.get_message(),
+        Some(Message::Command(Command::OpenWith { command, terminal: false })) if command == "gimp %f"
+    ));
+    assert_eq!(
+        Command::parse("open_with vim -p %F"),
+        Ok(Command::OpenWith {
+            command: "vim -p %F".to_owned(),
+            terminal: true
+        })
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_subshell() {
+    use crate::shell;
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    let level = std::env::var(shell::LEVEL_VAR)
+        .ok()
+        .and_then(|level| level.parse::<u32>().ok())
+        .unwrap_or(0);
+    let command = shell::subshell(Path::new("/tmp"));
+    assert_eq!(command.get_current_dir(), Some(Path::new("/tmp")));
+    let expected = (level + 1).to_string();
+    assert!(
+        command
+            .get_envs()
+            .any(|(key, value)| key == shell::LEVEL_VAR && value == Some(OsStr::new(&expected)))
+    );
+
+    let mut command = std::process::Command::new("sh");
+    command.args(["-c", "exit 3"]);
+    assert_eq!(shell::run_attached(&mut command).unwrap().code(), Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shell_command() {
+    use crate::command::Command;
+    use crate::config::Config;
+    use crate::event_loop::WorkerMessage;
+    use crate::keymap::{Action, KeyChord, KeyMap, KeyMatch};
+    use crate::shell;
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    assert_eq!(shell::quote(Path::new("it's")), "'it'\\''s'");
+    let selection = [PathBuf::from("/tmp/a b"), PathBuf::from("/tmp/c")];
+    assert_eq!(
+        shell::expand("tar czf %d/x.tgz %s 100%%", &selection, Path::new("/tmp")),
+        "tar czf '/tmp'/x.tgz '/tmp/a b' '/tmp/c' 100%"
+    );
+    //names that aren't UTF-8 reach the shell byte for byte
+    let latin1 = PathBuf::from(OsStr::from_bytes(b"caf\xe9"));
+    assert_eq!(
+        shell::expand("rm %s", &[latin1], Path::new("/")).as_bytes(),
+        b"rm 'caf\xe9'"
+    );
+
+    assert!(matches!(
+        Command::parse("!ls %s"),
+        Ok(Command::Shell { command, attached: true }) if command == "ls %s"
+    ));
+    assert!(matches!(Command::parse("run x"), Ok(Command::Run(name)) if name == "x"));
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let run = |script: &str, cancel: &Arc<AtomicBool>| {
+        shell::run_captured(
+            "test".to_owned(),
+            script.into(),
+            PathBuf::from("/tmp"),
+            cancel.clone(),
+            sender.clone(),
+        );
+    };
+    let output = || match receiver.recv_timeout(Duration::from_secs(10)) {
+        Ok(WorkerMessage::CommandOutput(output)) => output,
+        _ => panic!("no command output"),
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    run("echo out; echo err >&2; exit 2", &cancel);
+    let finished = output();
+    assert_eq!(finished.text, "out\nerr\n");
+    assert_eq!(finished.status.unwrap().code(), Some(2));
+    assert!(!finished.truncated);
+
+    //endless output is cut off and the command stopped
+    run("yes", &cancel);
+    let endless = output();
+    assert_eq!(endless.text.len(), shell::MAX_OUTPUT);
+    assert!(endless.truncated);
+
+    run("echo started; sleep 60", &cancel);
+    std::thread::sleep(Duration::from_millis(200));
+    cancel.store(true, Ordering::Relaxed);
+    let cancelled = output();
+    assert_eq!(cancelled.text, "started\n");
+    assert!(cancelled.truncated);
+
+    let config = Config::parse(
+        "[[actions]]\nname = \"archive\"\ncommand = \"tar czf a.tgz %s\"\nkeys = \"g a\"",
+    )
+    .unwrap();
+    assert_eq!(config.actions.len(), 1);
+    assert!(!config.actions[0].attached);
+    let mut keymap = KeyMap::default();
+    keymap.bind("g a", Action::Custom(0)).unwrap();
+    let chord = |c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE);
+    assert!(matches!(
+        keymap.lookup(&[chord('g'), chord('a')]),
+        KeyMatch::Action(Action::Custom(0))
+    ));
+    assert_eq!(
+        Action::from_name(&Action::Custom(0).name()),
+        Some(Action::Custom(0))
+    );
+    assert!(keymap.bind("hyper-x", Action::Custom(0)).is_err());
+}
+
+#[test]
+fn test_chooser() {
+    use crate::command::Command;
+    use crate::keymap::Action;
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::windows::explorer_table::{ChooseMode, ExplorerTable};
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("chooser");
+    std::fs::create_dir(dir.join("sub")).unwrap();
+    std::fs::write(dir.join("a"), "").unwrap();
+    std::fs::write(dir.join("b"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    file_manager.read_only = true;
+    wait_for_load(&mut file_manager, &receiver);
+    let sub_index = file_manager
+        .get_entries()
+        .iter()
+        .position(|entry| entry.name == "sub")
+        .unwrap();
+    let mut explorer_table = ExplorerTable::new(
+        Default::default(),
+        Default::default(),
+        Some(ChooseMode::Dir),
+    );
+    for _ in 0..sub_index {
+        explorer_table.run_action(Action::MoveDown, &mut file_manager);
+    }
+    match explorer_table.run_action(Action::Choose, &mut file_manager) {
+        AppEvents::Choose(paths) => assert_eq!(paths, [dir.join("sub")]),
+        _ => panic!("the folder under the cursor wasn't chosen"),
+    }
+
+    let mut explorer_table = ExplorerTable::new(
+        Default::default(),
+        Default::default(),
+        Some(ChooseMode::Files),
+    );
+    file_manager.add_to_selection(dir.join("b")).unwrap();
+    file_manager.add_to_selection(dir.join("a")).unwrap();
+    match explorer_table.run_action(Action::Choose, &mut file_manager) {
+        AppEvents::Choose(paths) => assert_eq!(paths, [dir.join("a"), dir.join("b")]),
+        _ => panic!("the selection wasn't chosen"),
+    }
+    assert!(matches!(
+        explorer_table.run_action(Action::Delete, &mut file_manager),
+        AppEvents::OpenErrorPopup(_)
+    ));
+    let open_with = Command::parse("open_with rm %F").unwrap();
+    assert!(matches!(
+        explorer_table.run_command(open_with, &mut file_manager),
+        AppEvents::OpenErrorPopup(_)
+    ));
+    assert!(matches!(
+        explorer_table.run_action(Action::OpenWith, &mut file_manager),
+        AppEvents::OpenErrorPopup(_)
+    ));
+    assert!(dir.join("a").exists());
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_explorer_view() {
+    use crate::keymap::{Action, KeyMap};
+    use crate::trash::Trash;
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::windows::explorer_table::{ExplorerTable, ExplorerView};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::layout::Rect;
+    use std::rc::Rc;
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("explorer_view");
+    std::fs::write(dir.join("embedded.txt"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    wait_for_load(&mut file_manager, &receiver);
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 12)).unwrap();
+    //only the lower half belongs to the explorer, like a pane of another program
+    terminal
+        .draw(|frame| {
+            let area = Rect::new(0, 6, 80, 6);
+            frame.render_stateful_widget(
+                ExplorerView::new(&file_manager),
+                area,
+                &mut explorer_table,
+            );
+        })
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+    assert!((0..6).all(|y| row(y).trim().is_empty()));
+    assert!((6..12).any(|y| row(y).contains("embedded.txt")));
+
+    //without the Controller the program asks the question itself
+    file_manager.trash = Some(Trash::new(dir.join("trash")));
+    file_manager
+        .add_to_selection(dir.join("embedded.txt"))
+        .unwrap();
+    assert!(matches!(
+        explorer_table.run_action(Action::Delete, &mut file_manager),
+        AppEvents::OpenConfirmationPopup
+    ));
+    assert!(
+        explorer_table
+            .take_prompt()
+            .unwrap()
+            .ends_with("are you sure?")
+    );
+    explorer_table.confirm(false, &mut file_manager);
+    assert!(dir.join("embedded.txt").exists());
+    explorer_table.run_action(Action::Delete, &mut file_manager);
+    assert!(matches!(
+        explorer_table.confirm(true, &mut file_manager),
+        AppEvents::None
+    ));
+    assert!(!dir.join("embedded.txt").exists());
+    //a prompt is only answered once
+    std::fs::write(dir.join("embedded.txt"), "").unwrap();
+    file_manager
+        .add_to_selection(dir.join("embedded.txt"))
+        .unwrap();
+    explorer_table.confirm(true, &mut file_manager);
+    assert!(dir.join("embedded.txt").exists());
+
+    //Ctrl chords reach the keymap like any other key
+    let mut keymap = KeyMap::default();
+    keymap.bind("C-t", Action::ToggleHidden).unwrap();
+    let mut explorer_table = ExplorerTable::new(Rc::new(keymap), Default::default(), None);
+    let hidden = file_manager.show_hidden;
+    let ctrl_t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL);
+    explorer_table.handle_key_event(ctrl_t, &mut file_manager);
+    assert_eq!(file_manager.show_hidden, !hidden);
+
+    //the preview is drawn once it was read in the background
+    std::fs::write(dir.join("embedded.txt"), "previewed text").unwrap();
+    std::fs::remove_dir_all(dir.join("trash")).unwrap();
+    file_manager.update();
+    wait_for_load(&mut file_manager, &receiver);
+    let config = crate::config::Config::parse("[preview]\nenabled = true").unwrap();
+    let mut explorer_table = ExplorerTable::new(Default::default(), Rc::new(config), None);
+    let mut draw = |file_manager: &crate::file_manager::FileManager| {
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                frame.render_stateful_widget(
+                    ExplorerView::new(file_manager),
+                    area,
+                    &mut explorer_table,
+                )
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..12).any(|y| {
+            (0..80)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+                .contains("previewed text")
+        })
+    };
+    assert!(!draw(&file_manager));
+    while file_manager.preview(&dir.join("embedded.txt")).is_none() {
+        let message = receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("no preview");
+        file_manager.handle_worker_message(message);
+    }
+    assert!(draw(&file_manager));
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_errors() {
+    use crate::error::{Error, Operation};
+    use crate::keymap::Action;
+    use crate::trash::Trash;
+    use crate::ui::controller::{AppEvents, State};
+    use crate::ui::message::{Message, MessageReceiver};
+    use crate::ui::windows::explorer_table::ExplorerTable;
+    use crate::ui::windows::popups::error_log_popup::ErrorLogPopup;
+    use std::time::SystemTime;
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = temp_dir("errors");
+    std::fs::write(dir.join("gone"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+
+    let (mut file_manager, receiver) = new_file_manager();
+    file_manager.trash = Some(Trash::new(dir.join("trash")));
+    wait_for_load(&mut file_manager, &receiver);
+    let error = file_manager.change_dir(dir.join("missing")).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Io {
+            operation: Operation::ChangeDir,
+            ..
+        }
+    ));
+    assert_eq!(error.path(), Some(dir.join("missing").as_path()));
+    assert!(error.to_string().starts_with("changing into "));
+    assert_eq!(file_manager.current_dir(), dir.canonicalize().unwrap());
+
+    //deleting a file that vanished after it was selected is reported, not a crash
+    std::fs::write(dir.join("deleted"), "").unwrap();
+    file_manager.add_to_selection(dir.join("deleted")).unwrap();
+    file_manager.add_to_selection(dir.join("gone")).unwrap();
+    std::fs::remove_file(dir.join("gone")).unwrap();
+    let mut explorer_table = ExplorerTable::new(Default::default(), Default::default(), None);
+    assert!(matches!(
+        explorer_table.run_action(Action::Delete, &mut file_manager),
+        AppEvents::OpenConfirmationPopup
+    ));
+    match explorer_table.handle_message(Some(Message::Bool(true)), &mut file_manager) {
+        AppEvents::OpenErrorPopup(Error::Io {
+            operation: Operation::Trash,
+            path,
+            ..
+        }) => assert_eq!(path, dir.join("gone")),
+        _ => panic!("the failed deletion wasn't reported"),
+    }
+    //what was deleted before the error isn't tried again
+    assert!(!dir.join("deleted").exists());
+    assert!(dir.join("trash/files/deleted").exists());
+    assert_eq!(file_manager.selection(), [dir.join("gone")]);
+
+    std::fs::write(dir.join("from"), "").unwrap();
+    std::fs::write(dir.join("to"), "").unwrap();
+    let error = file_manager
+        .rename(&dir.join("from"), &dir.join("to"))
+        .unwrap_err();
+    assert_eq!(error.path(), Some(dir.join("to").as_path()));
+
+    let error = file_manager
+        .create_file(dir.join("a/b"))
+        .and_then(|()| file_manager.create_file(dir.join("a/b/c")))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Io {
+            operation: Operation::CreateFolder,
+            ..
+        }
+    ));
+
+    let mut popup = ErrorLogPopup::new(&[
+        (SystemTime::now(), "first".to_owned()),
+        (SystemTime::now(), "second\n  spanning lines".to_owned()),
+    ]);
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 20)).unwrap();
+    terminal
+        .draw(|frame| popup.draw(frame, &mut file_manager))
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    let text: String = (0..20)
+        .map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+        .collect();
+    assert!(text.contains("ERRORS"));
+    assert!(text.contains("first") && text.contains("second") && text.contains("spanning lines"));
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_trash() {
+    use crate::trash::Trash;
+
+    let dir = temp_dir("trash");
+    let trash = Trash::new(dir.join("trash"));
+    std::fs::write(dir.join("a b"), "first").unwrap();
+    let trashed = trash.put(&dir.join("a b")).unwrap();
+    assert_eq!(trashed, dir.join("trash/files/a b"));
+    assert!(!dir.join("a b").exists());
+    let info = std::fs::read_to_string(dir.join("trash/info/a b.trashinfo")).unwrap();
+    let path = dir.join("a%20b").to_string_lossy().into_owned();
+    assert!(info.starts_with(&format!("[Trash Info]\nPath={}\nDeletionDate=", path)));
+
+    //a second file of the same name doesn't replace the first one
+    std::fs::create_dir(dir.join("a b")).unwrap();
+    let trashed = trash.put(&dir.join("a b")).unwrap();
+    assert_eq!(trashed, dir.join("trash/files/a b.2"));
+    assert!(dir.join("trash/info/a b.2.trashinfo").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("trash/files/a b")).unwrap(),
+        "first"
+    );
+
+    let error = trash.put(&dir.join("missing")).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,313 @@
+use crate::config::Config;
+use crate::entry::{Entry, EntryType};
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Styles of every part of the ui.
+/// Entries are colored by LS_COLORS if it is set, the entry styles of the theme are the fallback
+pub struct Theme {
+    pub border: Style,
+    pub title: Style,
+    pub header: Style,
+    /// the cell under the cursor
+    pub cursor: Style,
+    /// entries that were selected with <y>
+    pub selection: Style,
+    pub directory: Style,
+    pub file: Style,
+    pub symlink: Style,
+    pub executable: Style,
+    /// fifos, sockets and devices
+    pub special: Style,
+    pub error: Style,
+    /// the highlighted item of popup lists
+    pub popup_highlight: Style,
+    /// the bars of the disk usage window
+    pub bar: Style,
+    ls_colors: Option<LsColors>,
+}
+
+/// names of the styles that can be set in the [styles] table of config.toml
+pub const STYLE_NAMES: [&str; 13] = [
+    "border",
+    "title",
+    "header",
+    "cursor",
+    "selection",
+    "directory",
+    "file",
+    "symlink",
+    "executable",
+    "special",
+    "error",
+    "popup_highlight",
+    "bar",
+];
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            border: Style::new(),
+            title: Style::new().add_modifier(Modifier::BOLD),
+            header: Style::new()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+            cursor: Style::new()
+                .fg(Color::Green)
+                .add_modifier(Modifier::REVERSED),
+            selection: Style::new().bg(Color::DarkGray),
+            directory: Style::new().fg(Color::Blue),
+            file: Style::new(),
+            symlink: Style::new().fg(Color::Cyan),
+            executable: Style::new().fg(Color::LightGreen),
+            special: Style::new().fg(Color::Yellow),
+            error: Style::new().fg(Color::Red),
+            popup_highlight: Style::new().fg(Color::Red),
+            bar: Style::new(),
+            ls_colors: None,
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            border: Style::new().fg(Color::DarkGray),
+            title: Style::new().fg(Color::Black).add_modifier(Modifier::BOLD),
+            header: Style::new().fg(Color::Black).add_modifier(Modifier::BOLD),
+            cursor: Style::new()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::REVERSED),
+            selection: Style::new().bg(Color::Gray),
+            directory: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            file: Style::new(),
+            symlink: Style::new().fg(Color::Magenta),
+            executable: Style::new().fg(Color::Green),
+            special: Style::new().fg(Color::Rgb(0x99, 0x66, 0x00)),
+            error: Style::new().fg(Color::Red),
+            popup_highlight: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            bar: Style::new().fg(Color::Blue),
+            ls_colors: None,
+        }
+    }
+
+    /// a built in theme with the styles of the config applied on top
+    pub fn from_config(config: &Config) -> Result<Theme, String> {
+        let mut theme = match config.theme.as_str() {
+            "dark" => Theme::dark(),
+            "light" => Theme::light(),
+            name => {
+                return Err(format!(
+                    "unknown theme \"{}\", expected \"dark\" or \"light\"",
+                    name
+                ));
+            }
+        };
+        for (name, text) in &config.styles {
+            let style = parse_style(text).map_err(|e| format!("style \"{}\": {}", name, e))?;
+            theme.set_style(name, style)?;
+        }
+        if config.ls_colors
+            && let Ok(text) = std::env::var("LS_COLORS")
+        {
+            theme.ls_colors = Some(LsColors::parse(&text));
+        }
+        Ok(theme)
+    }
+
+    fn set_style(&mut self, name: &str, style: Style) -> Result<(), String> {
+        let field = match name {
+            "border" => &mut self.border,
+            "title" => &mut self.title,
+            "header" => &mut self.header,
+            "cursor" => &mut self.cursor,
+            "selection" => &mut self.selection,
+            "directory" => &mut self.directory,
+            "file" => &mut self.file,
+            "symlink" => &mut self.symlink,
+            "executable" => &mut self.executable,
+            "special" => &mut self.special,
+            "error" => &mut self.error,
+            "popup_highlight" => &mut self.popup_highlight,
+            "bar" => &mut self.bar,
+            _ => {
+                return Err(format!(
+                    "unknown style \"{}\", expected one of {}",
+                    name,
+                    STYLE_NAMES.join(", ")
+                ));
+            }
+        };
+        *field = style;
+        Ok(())
+    }
+
+    /// the style of the name of entry
+    pub fn entry_style(&self, entry: &Entry) -> Style {
+        if let Some(ls_colors) = &self.ls_colors
+            && let Some(style) = ls_colors.style(entry)
+        {
+            return style;
+        }
+        match entry.file_type {
+            EntryType::Dir => self.directory,
+            EntryType::Symlink => self.symlink,
+            EntryType::File if is_executable(entry) => self.executable,
+            EntryType::File => self.file,
+            _ => self.special,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+fn is_executable(entry: &Entry) -> bool {
+    entry.mode & 0o111 != 0
+}
+
+/// parses styles like `bold blue`, `black on #ffaf00` or `underlined 208`
+pub fn parse_style(text: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "crossed_out" => style.add_modifier(Modifier::CROSSED_OUT),
+            "on" => match words.next() {
+                Some(color) => style.bg(parse_color(color)?),
+                None => return Err("missing color after \"on\"".to_owned()),
+            },
+            color => style.fg(parse_color(color)?),
+        };
+    }
+    Ok(style)
+}
+
+fn parse_color(text: &str) -> Result<Color, String> {
+    Color::from_str(text).map_err(|_e| format!("unknown color \"{}\"", text))
+}
+
+/// The colors `ls` uses, parsed from the LS_COLORS environment variable,
+/// e.g. `di=01;34:ln=01;36:*.tar=01;31`
+pub struct LsColors {
+    /// two letter type codes like `di` or `ex`
+    types: HashMap<String, Style>,
+    /// lower case file name suffixes like `.tar`
+    suffixes: Vec<(String, Style)>,
+}
+
+impl LsColors {
+    pub fn parse(text: &str) -> LsColors {
+        let mut ls_colors = LsColors {
+            types: HashMap::new(),
+            suffixes: Vec::new(),
+        };
+        //broken entries are skipped like ls does
+        for (key, codes) in text.split(':').filter_map(|item| item.split_once('=')) {
+            let style = match parse_sgr(codes) {
+                Some(style) => style,
+                None => continue,
+            };
+            match key.strip_prefix('*') {
+                Some(suffix) => ls_colors.suffixes.push((suffix.to_lowercase(), style)),
+                None => {
+                    ls_colors.types.insert(key.to_owned(), style);
+                }
+            }
+        }
+        //longer suffixes are more specific, e.g. `.tar.gz` before `.gz`
+        ls_colors
+            .suffixes
+            .sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+        ls_colors
+    }
+
+    /// None if LS_COLORS has no color for this kind of entry
+    pub fn style(&self, entry: &Entry) -> Option<Style> {
+        let type_code = match entry.file_type {
+            EntryType::Dir => "di",
+            EntryType::Symlink if entry.broken_link => "or",
+            EntryType::Symlink => "ln",
+            EntryType::Fifo => "pi",
+            EntryType::Socket => "so",
+            EntryType::CharDevice => "cd",
+            EntryType::BlockDevice => "bd",
+            EntryType::File if is_executable(entry) => "ex",
+            EntryType::File | EntryType::Other => "fi",
+        };
+        if type_code == "fi" || type_code == "ex" {
+            //file names are matched case insensitive, like GNU ls does
+            let name = entry.name.to_string_lossy().to_lowercase();
+            for (suffix, style) in &self.suffixes {
+                if name.ends_with(suffix.as_str()) {
+                    return Some(*style);
+                }
+            }
+        }
+        //"ln=target" colors links like the file they point to, which is not supported
+        self.types
+            .get(type_code)
+            .or_else(|| {
+                if type_code == "or" {
+                    self.types.get("ln")
+                } else {
+                    None
+                }
+            })
+            .copied()
+    }
+}
+
+/// turns SGR codes like `01;38;5;208` into a style, None if they can't be parsed
+fn parse_sgr(codes: &str) -> Option<Style> {
+    let mut style = Style::new();
+    let mut numbers = codes.split(';').map(|n| {
+        if n.is_empty() {
+            Some(0)
+        } else {
+            n.parse::<u8>().ok()
+        }
+    });
+    while let Some(number) = numbers.next() {
+        style = match number? {
+            0 => Style::new(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            n @ 30..=37 => style.fg(Color::Indexed(n - 30)),
+            38 => style.fg(parse_extended_color(&mut numbers)?),
+            39 => style.fg(Color::Reset),
+            n @ 40..=47 => style.bg(Color::Indexed(n - 40)),
+            48 => style.bg(parse_extended_color(&mut numbers)?),
+            49 => style.bg(Color::Reset),
+            n @ 90..=97 => style.fg(Color::Indexed(n - 90 + 8)),
+            n @ 100..=107 => style.bg(Color::Indexed(n - 100 + 8)),
+            _ => style,
+        };
+    }
+    Some(style)
+}
+
+/// the rest of `38;5;n` or `38;2;r;g;b`
+fn parse_extended_color(numbers: &mut impl Iterator<Item = Option<u8>>) -> Option<Color> {
+    match numbers.next()?? {
+        5 => Some(Color::Indexed(numbers.next()??)),
+        2 => Some(Color::Rgb(
+            numbers.next()??,
+            numbers.next()??,
+            numbers.next()??,
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,214 @@
+//! The trash of the freedesktop.org trash spec, which file managers and `gio trash` share.
+//! A trashed file is moved into `files/` of a trash directory and described by an info file
+//! in `info/` with its original path, so other programs can restore it
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The home trash of the user and, for files on other devices, the trash on their device
+pub struct Trash {
+    /// e.g. ~/.local/share/Trash
+    home: PathBuf,
+}
+
+impl Trash {
+    pub fn new(home: PathBuf) -> Trash {
+        Trash { home }
+    }
+
+    /// the home trash at $XDG_DATA_HOME/Trash or ~/.local/share/Trash, None without a home
+    pub fn from_env() -> Option<Trash> {
+        let data_home = match std::env::var_os("XDG_DATA_HOME") {
+            Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(Trash::new(data_home.join("Trash")))
+    }
+
+    /// moves path into the trash, returns where it ended up
+    pub fn put(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = std::path::absolute(path)?;
+        //fails with NotFound for missing paths, a broken link is trashed itself
+        let metadata = path.symlink_metadata()?;
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "can't trash the root"))?;
+        let (trash_dir, original) = self.trash_dir_for(&path, &metadata)?;
+        fs::create_dir_all(trash_dir.join("files"))?;
+        fs::create_dir_all(trash_dir.join("info"))?;
+
+        //the info file is created first and exclusively, it reserves the name in files/
+        let (trashed_name, mut info) = reserve_name(&trash_dir, name)?;
+        let info_path = info_path(&trash_dir, &trashed_name);
+        let dest = trash_dir.join("files").join(&trashed_name);
+        let written = write!(
+            info,
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode_path(&original),
+            deletion_date(SystemTime::now())
+        );
+        match written.and_then(|()| fs::rename(&path, &dest)) {
+            Ok(()) => Ok(dest),
+            Err(e) => {
+                fs::remove_file(info_path).ok();
+                Err(e)
+            }
+        }
+    }
+
+    /// the trash directory on the device of path and the path as its info file records it.
+    /// Files can only be moved within a device, others go to $topdir/.Trash-$uid
+    fn trash_dir_for(
+        &self,
+        path: &Path,
+        metadata: &fs::Metadata,
+    ) -> io::Result<(PathBuf, PathBuf)> {
+        fs::create_dir_all(&self.home)?;
+        if device(&self.home.metadata()?) == device(metadata) {
+            return Ok((self.home.clone(), path.to_path_buf()));
+        }
+        let top_dir = mount_point(path, device(metadata));
+        let trash_dir = top_dir.join(format!(".Trash-{}", uid()));
+        create_private_dir(&trash_dir).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("the trash {} can't be created: {}", trash_dir.display(), e),
+            )
+        })?;
+        //paths in the trash of a device are relative to its top directory
+        let original = path.strip_prefix(&top_dir).unwrap_or(path).to_path_buf();
+        Ok((trash_dir, original))
+    }
+}
+
+fn info_path(trash_dir: &Path, name: &OsString) -> PathBuf {
+    let mut info_name = name.clone();
+    info_name.push(".trashinfo");
+    trash_dir.join("info").join(info_name)
+}
+
+/// creates the info file for name, or for name.2, name.3… if it is taken
+fn reserve_name(trash_dir: &Path, name: &std::ffi::OsStr) -> io::Result<(OsString, fs::File)> {
+    for n in 1.. {
+        let mut trashed_name = name.to_os_string();
+        if n > 1 {
+            trashed_name.push(format!(".{}", n));
+        }
+        if trash_dir
+            .join("files")
+            .join(&trashed_name)
+            .symlink_metadata()
+            .is_ok()
+        {
+            continue;
+        }
+        match fs::File::create_new(info_path(trash_dir, &trashed_name)) {
+            Ok(file) => return Ok((trashed_name, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
+
+/// percent-encodes path like an URL, as the spec wants it
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path_bytes(path) {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+/// the highest ancestor of path on the same device
+fn mount_point(path: &Path, dev: u64) -> PathBuf {
+    let mut top = path.to_path_buf();
+    for ancestor in path.ancestors().skip(1) {
+        match ancestor.metadata() {
+            Ok(metadata) if device(&metadata) == dev => top = ancestor.to_path_buf(),
+            _ => break,
+        }
+    }
+    top
+}
+
+/// creates dir readable only by the user, unless it exists
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    match builder.create(dir) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists && dir.is_dir() => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(unix)]
+fn device(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.dev()
+}
+
+#[cfg(not(unix))]
+fn device(_metadata: &fs::Metadata) -> u64 {
+    0
+}
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    unsafe { libc::getuid() }
+}
+
+#[cfg(not(unix))]
+fn uid() -> u32 {
+    0
+}
+
+/// local time as `YYYY-MM-DDThh:mm:ss`
+#[cfg(unix)]
+fn deletion_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        return String::new();
+    }
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+#[cfg(not(unix))]
+fn deletion_date(_time: SystemTime) -> String {
+    String::new()
+}
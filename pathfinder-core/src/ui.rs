@@ -0,0 +1,6 @@
+//! The windows and popups of the explorer. ExplorerView draws an ExplorerTable into any area,
+//! the Controller runs the whole explorer with its popups on a terminal
+
+pub mod controller;
+pub mod message;
+pub mod windows;
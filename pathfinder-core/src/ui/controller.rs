@@ -0,0 +1,404 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::Error;
+use crate::event_loop::{EventLoop, LoopEvent, WorkerMessage};
+use crate::file_manager::FileManager;
+use crate::keymap::{Action, KeyMap};
+use crate::theme::Theme;
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::ui::windows::disk_usage_window::DiskUsageWindow;
+use crate::ui::windows::explorer_table::ChooseMode;
+use crate::ui::windows::explorer_table::ExplorerTable;
+use crate::ui::windows::popups::columns_popup::ColumnsPopup;
+use crate::ui::windows::popups::command_line_popup::CommandLinePopup;
+use crate::ui::windows::popups::command_palette_popup::CommandPalettePopup;
+use crate::ui::windows::popups::confirmation_popup::ConfirmationPopup;
+use crate::ui::windows::popups::error_log_popup::ErrorLogPopup;
+use crate::ui::windows::popups::error_popup::ErrorPopup;
+use crate::ui::windows::popups::key_mapping_popup::KeyMappingPopup;
+use crate::ui::windows::popups::new_file_popup::NewFilePopup;
+use crate::ui::windows::popups::open_with_popup::OpenWithPopup;
+use crate::ui::windows::popups::output_popup::OutputPopup;
+use crate::ui::windows::popups::shell_prompt_popup::ShellPromptPopup;
+use crate::ui::windows::popups::sorting_popup::SortingPopUp;
+use crate::ui::windows::popups::text_field_popup::TextFieldPopup;
+use crossterm::event::{Event, KeyEvent, KeyEventKind, MouseEvent};
+use ratatui::Frame;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub enum AppEvents {
+    None,
+    Exit,
+    OpenSortingPopupWindow,
+    ChangeToExplorerWindow,
+    ChangeToDiskUsageWindow,
+    OpenKeyMappingPopupWindow,
+    OpenTextFieldPopup,
+    OpenNewFilePopup,
+    OpenConfirmationPopup,
+    OpenColumnsPopup,
+    OpenCommandPalette,
+    OpenCommandLine,
+    /// show the error and add it to the error log
+    OpenErrorPopup(Error),
+    OpenErrorLog,
+    OpenOpenWithPopup,
+    OpenShellPrompt,
+    /// suspend the ui while the program runs in the terminal
+    RunInTerminal(std::process::Command),
+    /// quit the chooser mode with these paths
+    Choose(Vec<PathBuf>),
+    ClosePopUp,
+}
+
+#[derive(Eq, Hash, PartialEq, Clone, Copy)]
+pub enum AppWindows {
+    Explorer = 0,
+    DiskUsage = 1,
+}
+
+pub trait State: MessageReceiver + MessageSender {
+    fn enter(&mut self, _file_manager: &mut FileManager) {}
+    fn exit(&mut self, _file_manager: &mut FileManager) {}
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents;
+    fn handle_mouse_event(
+        &mut self,
+        _mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        AppEvents::None
+    }
+    fn run_action(&mut self, _action: Action, _file_manager: &mut FileManager) -> AppEvents {
+        AppEvents::None
+    }
+    fn run_command(&mut self, _command: Command, _file_manager: &mut FileManager) -> AppEvents {
+        AppEvents::None
+    }
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager);
+}
+
+pub struct Controller {
+    pub all_windows: [Box<dyn State>; 2],
+    pub current_window_index: AppWindows,
+    pub popup_stack: Vec<Box<dyn State>>,
+    pub file_manager: FileManager,
+    event_loop: EventLoop,
+    keymap: Rc<KeyMap>,
+    config: Rc<Config>,
+    /// every error shown in this session, for the error log
+    error_log: Vec<(SystemTime, String)>,
+}
+
+impl Controller {
+    pub fn new(chooser: Option<ChooseMode>) -> Controller {
+        let event_loop = EventLoop::new();
+        //broken config files fall back to the defaults, the errors are shown once the ui is up
+        let mut errors = Vec::new();
+        let mut keymap = KeyMap::load().unwrap_or_else(|e| {
+            errors.push(Error::Config(e));
+            KeyMap::default()
+        });
+        let config = Rc::new(Config::load().unwrap_or_else(|e| {
+            errors.push(Error::Config(e));
+            Config::default()
+        }));
+        for (index, action) in config.actions.iter().enumerate() {
+            if let Some(keys) = &action.keys
+                && let Err(e) = keymap.bind(keys, Action::Custom(index))
+            {
+                errors.push(Error::Config(format!("action \"{}\": {}", action.name, e)));
+            }
+        }
+        if chooser.is_some() {
+            keymap
+                .bind("Enter", Action::Choose)
+                .expect("Enter is a valid key");
+        }
+        let keymap = Rc::new(keymap);
+        let theme = Theme::from_config(&config).unwrap_or_else(|e| {
+            errors.push(Error::Config(e));
+            Theme::default()
+        });
+        let mut file_manager = FileManager::new(event_loop.worker_sender(), &config);
+        file_manager.theme = theme;
+        file_manager.read_only = chooser.is_some();
+        let mut controller = Controller {
+            all_windows: [
+                Box::new(ExplorerTable::new(keymap.clone(), config.clone(), chooser)),
+                Box::new(DiskUsageWindow::new(config.clone())),
+            ],
+            current_window_index: AppWindows::Explorer,
+            popup_stack: Vec::new(),
+            file_manager,
+            event_loop,
+            keymap,
+            config,
+            error_log: Vec::new(),
+        };
+        for error in errors {
+            controller.show_error(error);
+        }
+        controller
+    }
+
+    pub fn change_window(&mut self, new_window: AppWindows) {
+        self.all_windows[self.current_window_index as usize].exit(&mut self.file_manager);
+        self.current_window_index = new_window;
+        self.all_windows[self.current_window_index as usize].enter(&mut self.file_manager);
+    }
+
+    /// stop reading input while another program uses the terminal
+    pub fn pause_input(&self) {
+        self.event_loop.pause_input();
+    }
+
+    pub fn resume_input(&self) {
+        self.event_loop.resume_input();
+    }
+
+    /// shows error in a popup until a key is pressed, it stays in the error log
+    pub fn show_error(&mut self, error: Error) {
+        let text = error.to_string();
+        self.error_log.push((SystemTime::now(), text.clone()));
+        self.popup_stack.push(Box::new(ErrorPopup::new(text)));
+    }
+
+    /// the errors of this session, the oldest first
+    pub fn error_log(&self) -> &[(SystemTime, String)] {
+        &self.error_log
+    }
+
+    /// waits for the next event and handles it together with all events that piled up meanwhile
+    pub fn handle_events(&mut self) -> io::Result<AppEvents> {
+        let event = match self.event_loop.next() {
+            Ok(event) => event,
+            Err(_e) => return Err(io::Error::other("event loop stopped")),
+        };
+        let mut app_event = self.handle_loop_event(event)?;
+        while let AppEvents::None = app_event
+            && let Some(event) = self.event_loop.try_next()
+        {
+            app_event = self.handle_loop_event(event)?;
+        }
+        self.file_manager.sort_if_changed();
+        Ok(app_event)
+    }
+
+    fn handle_loop_event(&mut self, event: LoopEvent) -> io::Result<AppEvents> {
+        match event {
+            LoopEvent::Input(event) => {
+                //key handling works with indices, so the entries have to be in their final order
+                self.file_manager.sort_if_changed();
+                self.handle_input_event(event)
+            }
+            //nothing to do besides drawing again
+            LoopEvent::Tick => Ok(AppEvents::None),
+            LoopEvent::Worker(WorkerMessage::CommandOutput(output)) => {
+                self.popup_stack.push(Box::new(OutputPopup::new(output)));
+                //the command may have changed files the watcher doesn't see, e.g. in subfolders
+                let current_dir = self.file_manager.current_dir().to_path_buf();
+                self.file_manager.invalidate(&current_dir);
+                self.file_manager.update();
+                Ok(AppEvents::None)
+            }
+            LoopEvent::Worker(message) => {
+                self.file_manager.handle_worker_message(message);
+                Ok(AppEvents::None)
+            }
+            LoopEvent::InputError(e) => Err(e),
+        }
+    }
+
+    fn handle_input_event(&mut self, event: Event) -> io::Result<AppEvents> {
+        match event {
+            // it's important to check that the event is a key press event as
+            // crossterm also emits key release and repeat events on Windows.
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                //if popups are active, handle popup instead of window
+                let app_event: AppEvents = if let Some(popup) = self.popup_stack.last_mut() {
+                    popup.handle_key_event(key_event, &mut self.file_manager)
+                } else {
+                    self.all_windows[self.current_window_index as usize]
+                        .handle_key_event(key_event, &mut self.file_manager)
+                };
+
+                self.handle_app_event(app_event)
+            }
+            Event::Mouse(mouse_event) => {
+                let app_event: AppEvents = if let Some(popup) = self.popup_stack.last_mut() {
+                    popup.handle_mouse_event(mouse_event, &mut self.file_manager)
+                } else {
+                    self.all_windows[self.current_window_index as usize]
+                        .handle_mouse_event(mouse_event, &mut self.file_manager)
+                };
+                self.handle_app_event(app_event)
+            }
+            _ => Ok(AppEvents::None),
+        }
+    }
+
+    /// carry out what a window or popup requested
+    fn handle_app_event(&mut self, app_event: AppEvents) -> io::Result<AppEvents> {
+        match app_event {
+            AppEvents::None => Ok(AppEvents::None),
+            AppEvents::Exit => Ok(AppEvents::Exit),
+            //only the app can give away the terminal
+            AppEvents::RunInTerminal(command) => Ok(AppEvents::RunInTerminal(command)),
+            AppEvents::Choose(paths) => Ok(AppEvents::Choose(paths)),
+            AppEvents::OpenSortingPopupWindow => {
+                self.popup_stack
+                    .push(Box::new(SortingPopUp::new(None, &mut self.file_manager)));
+                Ok(AppEvents::None)
+            }
+            AppEvents::ChangeToExplorerWindow => {
+                self.change_window(AppWindows::Explorer);
+                Ok(AppEvents::None)
+            }
+            AppEvents::ChangeToDiskUsageWindow => {
+                self.change_window(AppWindows::DiskUsage);
+                Ok(AppEvents::None)
+            }
+            AppEvents::OpenKeyMappingPopupWindow => {
+                self.popup_stack
+                    .push(Box::new(KeyMappingPopup::new(self.keymap.clone())));
+                Ok(AppEvents::None)
+            }
+            AppEvents::OpenTextFieldPopup => {
+                let message = self.get_current_message();
+                self.popup_stack.push(Box::new(TextFieldPopup::new(
+                    message,
+                    &mut self.file_manager,
+                )));
+                Ok(AppEvents::None)
+            }
+            AppEvents::OpenConfirmationPopup => {
+                let message = self.get_current_message();
+                self.popup_stack.push(Box::new(ConfirmationPopup::new(
+                    message,
+                    &mut self.file_manager,
+                )));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenColumnsPopup => {
+                self.popup_stack
+                    .push(Box::new(ColumnsPopup::new(None, &mut self.file_manager)));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenNewFilePopup => {
+                self.popup_stack
+                    .push(Box::new(NewFilePopup::new(None, &mut self.file_manager)));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenCommandPalette => {
+                self.popup_stack
+                    .push(Box::new(CommandPalettePopup::new(self.keymap.clone())));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenCommandLine => {
+                self.popup_stack.push(Box::new(CommandLinePopup::new()));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenErrorPopup(error) => {
+                self.show_error(error);
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenErrorLog => {
+                self.popup_stack
+                    .push(Box::new(ErrorLogPopup::new(&self.error_log)));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenOpenWithPopup => {
+                if let Some(Message::Path(path)) = self.get_current_message() {
+                    self.popup_stack
+                        .push(Box::new(OpenWithPopup::new(path, &self.config)));
+                }
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::OpenShellPrompt => {
+                self.popup_stack.push(Box::new(ShellPromptPopup::new()));
+                Ok(AppEvents::None)
+            }
+
+            AppEvents::ClosePopUp => {
+                //pass down message
+                let message = self.get_current_message();
+                if self.popup_stack.pop().is_none() {
+                    return Ok(AppEvents::None);
+                }
+                if let Some(Message::Action(action)) = message {
+                    let app_event = self.run_current_action(action);
+                    return self.handle_app_event(app_event);
+                }
+                if let Some(Message::Command(command)) = message {
+                    let app_event = self.run_current_command(command);
+                    return self.handle_app_event(app_event);
+                }
+                let app_event = self.send_current_message(message);
+                self.handle_app_event(app_event)
+            }
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        //Draw base window then all popups
+
+        self.all_windows[self.current_window_index as usize].draw(frame, &mut self.file_manager);
+        for x in &mut self.popup_stack {
+            x.draw(frame, &mut self.file_manager);
+        }
+    }
+
+    /// Get message from currently active window
+    pub fn get_current_message(&mut self) -> Option<Message> {
+        if let Some(popup) = self.popup_stack.last_mut() {
+            popup.get_message()
+        } else {
+            self.all_windows[self.current_window_index as usize].get_message()
+        }
+    }
+
+    /// Let the currently active window run an action
+    pub fn run_current_action(&mut self, action: Action) -> AppEvents {
+        if let Some(popup) = self.popup_stack.last_mut() {
+            popup.run_action(action, &mut self.file_manager)
+        } else {
+            self.all_windows[self.current_window_index as usize]
+                .run_action(action, &mut self.file_manager)
+        }
+    }
+
+    /// Let the currently active window run a command of the command line
+    pub fn run_current_command(&mut self, command: Command) -> AppEvents {
+        if let Some(popup) = self.popup_stack.last_mut() {
+            popup.run_command(command, &mut self.file_manager)
+        } else {
+            self.all_windows[self.current_window_index as usize]
+                .run_command(command, &mut self.file_manager)
+        }
+    }
+
+    /// Send message to currently active window
+    pub fn send_current_message(&mut self, message: Option<Message>) -> AppEvents {
+        if let Some(popup) = self.popup_stack.last_mut() {
+            popup.handle_message(message, &mut self.file_manager)
+        } else {
+            self.all_windows[self.current_window_index as usize]
+                .handle_message(message, &mut self.file_manager)
+        }
+    }
+}
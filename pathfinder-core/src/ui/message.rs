@@ -0,0 +1,33 @@
+use crate::command::Command;
+use crate::keymap::Action;
+use crate::ui::controller::AppEvents;
+use std::path::PathBuf;
+
+pub enum Message {
+    String(String),
+    Bool(bool),
+    /// a file name, kept as it is even if it isn't valid UTF-8
+    Path(PathBuf),
+    /// run by the window or popup below the popup that sent it
+    Action(Action),
+    /// a line of the command line, run like an action
+    Command(Command),
+}
+
+pub trait MessageSender {
+    fn get_message(&mut self) -> Option<Message> {
+        None
+    }
+}
+
+pub trait MessageReceiver {
+    /// what is carried out because of the message can fail, so an event like the error popup
+    /// may be returned
+    fn handle_message(
+        &mut self,
+        _message: Option<Message>,
+        _file_manager: &mut crate::file_manager::FileManager,
+    ) -> AppEvents {
+        AppEvents::None
+    }
+}
@@ -1,2 +1,3 @@
+pub mod disk_usage_window;
 pub mod explorer_table;
 pub mod popups;
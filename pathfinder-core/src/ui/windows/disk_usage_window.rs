@@ -0,0 +1,238 @@
+use crate::config::Config;
+use crate::disk_usage::DuNode;
+use crate::error::Error;
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::prelude::Line;
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Cell, Row, Table, TableState};
+use std::ffi::OsString;
+use std::rc::Rc;
+
+const BAR_WIDTH: usize = 20;
+
+//this enum is used to know which part of the window requested the popup to properly handle the
+//message
+enum MessageSource {
+    None,
+    DeletionConfirmationPrompt,
+}
+
+/// ncdu-like view of the last disk usage scan of the FileManager.
+/// Entries are sorted by their aggregate size
+pub struct DiskUsageWindow {
+    table_state: TableState,
+    /// names from the root of the scan to the currently shown directory
+    path: Vec<OsString>,
+    message_source: MessageSource,
+    message: Option<Message>,
+    config: Rc<Config>,
+}
+
+impl DiskUsageWindow {
+    pub fn new(config: Rc<Config>) -> DiskUsageWindow {
+        let mut window = DiskUsageWindow {
+            table_state: TableState::new(),
+            path: Vec::new(),
+            message_source: MessageSource::None,
+            message: None,
+            config,
+        };
+        window.table_state.select_first();
+        window
+    }
+
+    fn current_node<'a>(&self, file_manager: &'a FileManager) -> Option<&'a DuNode> {
+        let (_root, node) = file_manager.disk_usage()?;
+        node.get(&self.path)
+    }
+
+    fn selected_child<'a>(&self, file_manager: &'a FileManager) -> Option<&'a DuNode> {
+        let index = self.table_state.selected()?;
+        self.current_node(file_manager)?.children.get(index)
+    }
+
+    fn delete_selected(&mut self, file_manager: &mut FileManager) -> AppEvents {
+        let name = match self.selected_child(file_manager) {
+            Some(child) => child.name.clone(),
+            None => return AppEvents::None,
+        };
+        let mut path = self.path.clone();
+        path.push(name);
+        match file_manager.delete_from_disk_usage(&path) {
+            Ok(()) => AppEvents::None,
+            Err(e) => AppEvents::OpenErrorPopup(e),
+        }
+    }
+
+    fn size_bar(size: u64, total: u64) -> String {
+        let filled = if total == 0 {
+            0
+        } else {
+            (size as f64 / total as f64 * BAR_WIDTH as f64).round() as usize
+        };
+        format!("[{}{}]", "█".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+    }
+}
+
+impl MessageReceiver for DiskUsageWindow {
+    fn handle_message(
+        &mut self,
+        message: Option<Message>,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let message_source = std::mem::replace(&mut self.message_source, MessageSource::None);
+        match message_source {
+            MessageSource::DeletionConfirmationPrompt => match message {
+                Some(Message::Bool(true)) => self.delete_selected(file_manager),
+                _ => AppEvents::None,
+            },
+            MessageSource::None => AppEvents::None,
+        }
+    }
+}
+
+impl MessageSender for DiskUsageWindow {
+    fn get_message(&mut self) -> Option<Message> {
+        self.message.take()
+    }
+}
+
+impl State for DiskUsageWindow {
+    fn enter(&mut self, file_manager: &mut FileManager) {
+        self.path.clear();
+        self.table_state.select_first();
+        file_manager.scan_disk_usage();
+    }
+
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => return AppEvents::ChangeToExplorerWindow,
+            KeyCode::Down | KeyCode::Char('j') => self.table_state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.table_state.select_previous(),
+            //drill down into the selected directory
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
+                if let Some(child) = self.selected_child(file_manager)
+                    && child.is_dir
+                {
+                    self.path.push(child.name.clone());
+                    self.table_state.select_first();
+                }
+            }
+            //go back up, keeping the cursor on the directory we came from
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => {
+                if let Some(name) = self.path.pop() {
+                    let index = self
+                        .current_node(file_manager)
+                        .and_then(|node| node.children.iter().position(|c| c.name == name));
+                    self.table_state.select(Some(index.unwrap_or(0)));
+                }
+            }
+            KeyCode::Char('r') => {
+                self.enter(file_manager);
+            }
+            KeyCode::Char('x') if file_manager.read_only => {
+                return AppEvents::OpenErrorPopup(Error::Refused(
+                    "deleting is disabled while choosing files".to_owned(),
+                ));
+            }
+            KeyCode::Char('x') if file_manager.is_disk_usage_scanning() => {
+                return AppEvents::OpenErrorPopup(Error::Refused(
+                    "wait for the scan to finish before deleting".to_owned(),
+                ));
+            }
+            KeyCode::Char('x') => {
+                let child = match self.selected_child(file_manager) {
+                    Some(child) => child,
+                    None => return AppEvents::None,
+                };
+                if !self.config.confirm.delete {
+                    return self.delete_selected(file_manager);
+                }
+                self.message_source = MessageSource::DeletionConfirmationPrompt;
+                self.message = Some(Message::String(format!(
+                    "{} ({}) will be deleted permanently, are you sure?",
+                    child.name.to_string_lossy(),
+                    util::format_size(child.size, file_manager.size_format)
+                )));
+                return AppEvents::OpenConfirmationPopup;
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let mut title = String::from("DISK USAGE");
+        if let Some((root, _node)) = file_manager.disk_usage() {
+            let current = self.path.iter().fold(root.to_path_buf(), |p, n| p.join(n));
+            title = format!("{} {}", title, current.display());
+        }
+        let theme = &file_manager.theme;
+        let help_text =
+            Line::from("<h/l> up/down, <x> delete, <r> rescan, <q> back").style(theme.title);
+        let block = Block::bordered()
+            .title(Line::from(title).left_aligned().style(theme.title))
+            .border_set(border::THICK)
+            .border_style(theme.border)
+            .title_bottom(help_text.right_aligned());
+
+        let node = match self.current_node(file_manager) {
+            Some(node) if !file_manager.is_disk_usage_scanning() => node,
+            _ => {
+                let text = if file_manager.is_disk_usage_scanning() {
+                    "scanning…"
+                } else {
+                    "nothing scanned"
+                };
+                let table =
+                    Table::new(vec![Row::new(vec![text])], [Constraint::Fill(1)]).block(block);
+                frame.render_widget(table, frame.area());
+                return;
+            }
+        };
+
+        let header = Row::new(vec!["SIZE", "", "NAME"]).style(theme.header);
+        let rows: Vec<Row> = node
+            .children
+            .iter()
+            .map(|child| {
+                let mut name = child.name.to_string_lossy().into_owned();
+                if child.is_dir {
+                    name.push('/');
+                }
+                let style = if child.is_dir {
+                    theme.directory
+                } else {
+                    theme.file
+                };
+                Row::new(vec![
+                    Cell::from(util::format_size(child.size, file_manager.size_format)),
+                    Cell::from(DiskUsageWindow::size_bar(child.size, node.size)).style(theme.bar),
+                    Cell::from(name),
+                ])
+                .style(style)
+            })
+            .collect();
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(BAR_WIDTH as u16 + 2),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .row_highlight_style(theme.selection);
+
+        frame.render_stateful_widget(table, frame.area(), &mut self.table_state);
+    }
+}
@@ -0,0 +1,816 @@
+use crate::column::Column;
+use crate::command::Command;
+use crate::config::Config;
+use crate::error::{self, Error};
+use crate::file_manager::{FileManager, SortDir};
+use crate::keymap::{Action, KeyChord, KeyMap, KeyMatch};
+use crate::opener::{self, Launch};
+use crate::shell;
+use crate::sorting::Sorting;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::util::{self, SizeFormat};
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Margin, Position, Rect};
+use ratatui::prelude::Line;
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Paragraph, Row, StatefulWidget, Table, TableState, Widget};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+///two clicks on the same entry within this time count as a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+///entries moved per step of the scroll wheel
+const SCROLL_LINES: isize = 3;
+
+/// What is picked when the explorer runs as a picker for another program
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChooseMode {
+    Files,
+    Dir,
+}
+
+//this enum is used to know which part of the window requested the popup to properly handle the
+//message
+enum MessageSource {
+    None,
+    DeletionConfirmationPrompt,
+    PasteConfirmationPrompt,
+}
+
+pub struct ExplorerTable {
+    ///the selected index refers to all entries of the FileManager, not only the visible ones
+    table_state: TableState,
+    ///index of the first visible entry
+    offset: usize,
+    ///number of entries that fit into the table, updated on every draw
+    viewport_height: usize,
+    keymap: Rc<KeyMap>,
+    config: Rc<Config>,
+    ///the last preview asked for: the path, the preview generation and the number of lines
+    preview_request: Option<(PathBuf, u64, usize)>,
+    ///keys of an unfinished multi-key sequence like <g g>
+    pending_keys: Vec<KeyChord>,
+    ///name of the entry under the cursor, so the cursor can follow it when the listing changes
+    selected_name: Option<OsString>,
+    ///listing version of the FileManager at the last draw
+    listing_version: u64,
+    ///area of the table at the last draw, used to map mouse clicks to entries
+    area: Rect,
+    ///the visible columns and their header areas at the last draw
+    column_areas: Vec<(Column, Rect)>,
+    ///time and entry index of the last left click, to detect double clicks
+    last_click: Option<(Instant, usize)>,
+    ///Some if the explorer runs as a picker for another program
+    chooser: Option<ChooseMode>,
+    message_source: MessageSource,
+    message: Option<Message>,
+}
+
+impl ExplorerTable {
+    pub fn new(
+        keymap: Rc<KeyMap>,
+        config: Rc<Config>,
+        chooser: Option<ChooseMode>,
+    ) -> ExplorerTable {
+        let mut explorer_table = ExplorerTable {
+            table_state: TableState::new(),
+            offset: 0,
+            viewport_height: 1,
+            keymap,
+            config,
+            preview_request: None,
+            pending_keys: Vec::new(),
+            selected_name: None,
+            listing_version: 0,
+            area: Rect::default(),
+            column_areas: Vec::new(),
+            last_click: None,
+            chooser,
+            message_source: MessageSource::None,
+            message: None,
+        };
+        explorer_table.table_state.select_first_column();
+        explorer_table.table_state.select_first();
+        explorer_table
+    }
+
+    /// the question after AppEvents::OpenConfirmationPopup, for programs that show the
+    /// prompt themselves instead of the Controller. Answer it with confirm
+    pub fn take_prompt(&mut self) -> Option<String> {
+        match self.message.take() {
+            Some(Message::String(question)) => Some(question),
+            _ => None,
+        }
+    }
+
+    /// answers the prompt of take_prompt, yes carries out the delete or paste.
+    /// Returns AppEvents::OpenErrorPopup if that fails
+    pub fn confirm(&mut self, yes: bool, file_manager: &mut FileManager) -> AppEvents {
+        self.handle_message(Some(Message::Bool(yes)), file_manager)
+    }
+
+    /// get the corresponding file to the one that is selected in table_state.
+    /// Returns Option with the PathBuf or None if no File is currently selected
+    pub fn selected_file_in_table(&self, file_manager: &mut FileManager) -> Option<PathBuf> {
+        let index = self.table_state.selected()?;
+        let entry = match file_manager.get_entry_at_index(index) {
+            Ok(entry) => entry,
+            Err(_e) => {
+                return None;
+            }
+        };
+        Some(entry.path.clone())
+    }
+
+    /// change into the directory under the cursor or open the file under it
+    fn open_selected(&mut self, file_manager: &mut FileManager) -> AppEvents {
+        let index = match self.table_state.selected() {
+            Some(index) => index,
+            None => return AppEvents::None,
+        };
+        let entry = match file_manager.get_entry_at_index(index) {
+            Ok(entry) => entry,
+            Err(_e) => return AppEvents::None,
+        };
+        if !entry.is_dir() {
+            let path = entry.path.clone();
+            return self.open_file(&path);
+        }
+        if let Err(e) = file_manager.change_dir(entry.path.clone()) {
+            return AppEvents::OpenErrorPopup(e);
+        }
+        if self.table_state.selected().is_none() {
+            self.table_state.select(Some(0));
+        }
+        AppEvents::None
+    }
+
+    fn open_file(&mut self, path: &Path) -> AppEvents {
+        match opener::launch_for(path, &self.config) {
+            Ok(launch) => self.launch(launch),
+            Err(e) => {
+                AppEvents::OpenErrorPopup(Error::Command(format!("{}: {}", path.display(), e)))
+            }
+        }
+    }
+
+    fn launch(&mut self, launch: Launch) -> AppEvents {
+        match launch {
+            Launch::Terminal(command) => AppEvents::RunInTerminal(command),
+            Launch::Detached(mut command) => match opener::spawn_detached(&mut command) {
+                Ok(()) => AppEvents::None,
+                Err(e) => AppEvents::OpenErrorPopup(Error::Launch {
+                    program: opener::program_name(&command),
+                    source: e,
+                }),
+            },
+        }
+    }
+
+    /// Picks the paths for the chooser mode: the selection, otherwise the entry under the cursor.
+    /// Choosing files enters a folder under the cursor, choosing a folder falls back to the
+    /// current one if the cursor is on a file
+    fn choose(&mut self, mode: ChooseMode, file_manager: &mut FileManager) -> AppEvents {
+        let selection = file_manager.selection();
+        if mode == ChooseMode::Dir && selection.iter().any(|path| !path.is_dir()) {
+            return AppEvents::OpenErrorPopup(Error::Refused(
+                "only folders can be chosen".to_owned(),
+            ));
+        }
+        if !selection.is_empty() {
+            return AppEvents::Choose(selection);
+        }
+        let cursor = self
+            .table_state
+            .selected()
+            .and_then(|index| file_manager.get_entry_at_index(index).ok())
+            .map(|entry| (entry.path.clone(), entry.is_dir()));
+        match (mode, cursor) {
+            (ChooseMode::Files, Some((_, true))) => self.open_selected(file_manager),
+            (ChooseMode::Files, Some((path, false))) => AppEvents::Choose(vec![path]),
+            (ChooseMode::Files, None) => AppEvents::None,
+            (ChooseMode::Dir, Some((path, true))) => AppEvents::Choose(vec![path]),
+            (ChooseMode::Dir, _) => {
+                AppEvents::Choose(vec![file_manager.current_dir().to_path_buf()])
+            }
+        }
+    }
+
+    /// sort by the clicked column, clicking the active column again flips the direction
+    fn sort_by_column_at(&mut self, x: u16, file_manager: &mut FileManager) {
+        let column = self
+            .column_areas
+            .iter()
+            .find(|(_, area)| area.x <= x && x < area.x + area.width)
+            .map(|(column, _)| *column);
+        let key = match column.and_then(|c| c.sort_key()) {
+            Some(key) => key,
+            None => return,
+        };
+        let sorting = match file_manager.sorting() {
+            Sorting::Ascending(active) if active == key => Sorting::Descending(key),
+            _ => Sorting::Ascending(key),
+        };
+        file_manager.sort(sorting);
+    }
+
+    fn draw_preview(&mut self, buf: &mut Buffer, area: Rect, file_manager: &FileManager) {
+        let entry = self
+            .table_state
+            .selected()
+            .and_then(|index| file_manager.get_entry_at_index(index).ok());
+        let path = entry.map(|e| e.path.clone());
+        let max_lines = area.height.saturating_sub(2) as usize;
+        //an older preview of path, e.g. from before the area grew, is shown until the new one arrived
+        let preview = path.as_deref().and_then(|path| file_manager.preview(path));
+        //the preview is read in the background when the cursor moves, or again when path
+        //changed or the area grew. While it loads the request stays the same
+        let request = path
+            .clone()
+            .map(|path| (path, file_manager.preview_generation(), max_lines));
+        if request != self.preview_request {
+            let moved = self.preview_request.as_ref().map(|(p, _, _)| p) != path.as_ref();
+            match &path {
+                Some(path) if moved || !preview.is_some_and(|p| p.covers(max_lines)) => {
+                    file_manager.load_preview(path, self.config.preview.max_bytes, max_lines)
+                }
+                Some(_path) => {}
+                None => file_manager.watch_preview(None),
+            }
+            self.preview_request = request;
+        }
+        let lines: Vec<Line> = match preview {
+            Some(preview) => preview
+                .lines
+                .iter()
+                .map(|l| Line::from(l.as_str()))
+                .collect(),
+            None => Vec::new(),
+        };
+        let theme = &file_manager.theme;
+        let block = Block::bordered()
+            .title(Line::from("PREVIEW").style(theme.title))
+            .border_set(border::THICK)
+            .border_style(theme.border);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+
+    /// the selection is kept if pasting fails, so it can be tried again
+    fn paste(file_manager: &mut FileManager) -> AppEvents {
+        match file_manager.paste() {
+            Ok(()) => {
+                file_manager.clear_selection();
+                AppEvents::None
+            }
+            Err(e) => AppEvents::OpenErrorPopup(e),
+        }
+    }
+
+    fn delete_selection(file_manager: &mut FileManager) -> AppEvents {
+        match file_manager.delete_selection() {
+            Ok(()) => AppEvents::None,
+            Err(e) => AppEvents::OpenErrorPopup(e),
+        }
+    }
+
+    /// carries out a command of the command line, errors are meant for the error popup
+    fn execute(
+        &mut self,
+        command: Command,
+        file_manager: &mut FileManager,
+    ) -> error::Result<AppEvents> {
+        //open_with runs any command line on the selection, like a shell command
+        let destructive = matches!(
+            command,
+            Command::Rename(..)
+                | Command::Shell { .. }
+                | Command::Run(_)
+                | Command::OpenWith { .. }
+        );
+        if destructive && file_manager.read_only {
+            return Err(Error::Refused(
+                "this command is disabled while choosing files".to_owned(),
+            ));
+        }
+        match command {
+            Command::Mkdir(paths) => {
+                for path in paths {
+                    file_manager.create_folder(path)?;
+                }
+            }
+            Command::Touch(paths) => {
+                for path in paths {
+                    file_manager.touch(path)?;
+                }
+            }
+            Command::Sort(sorting) => file_manager.sort(sorting),
+            Command::Cd(path) => {
+                file_manager.change_dir(path)?;
+                self.selected_name = None;
+                self.table_state.select(Some(0));
+            }
+            Command::SetHidden(show_hidden) => {
+                file_manager.show_hidden = show_hidden.unwrap_or(!file_manager.show_hidden);
+                file_manager.update();
+            }
+            Command::Select(patterns) => {
+                let paths: Vec<PathBuf> = file_manager
+                    .get_entries()
+                    .iter()
+                    .filter(|entry| {
+                        let name = entry.name.to_string_lossy();
+                        //like in the shell, hidden files only match patterns starting with a dot
+                        patterns.iter().any(|pattern| {
+                            (!name.starts_with('.') || pattern.starts_with('.'))
+                                && util::glob_match(pattern, &name)
+                        })
+                    })
+                    .map(|entry| entry.path.clone())
+                    .collect();
+                if paths.is_empty() {
+                    return Err(Error::Command(format!(
+                        "no entries match {}",
+                        patterns.join(" ")
+                    )));
+                }
+                for path in paths {
+                    file_manager.add_to_selection(path)?;
+                }
+            }
+            Command::Rename(from, to) => {
+                let from = match from {
+                    Some(from) => from,
+                    None => self
+                        .selected_file_in_table(file_manager)
+                        .ok_or(Error::Command("there is no entry to rename".to_owned()))?,
+                };
+                file_manager.rename(&from, &to)?;
+                //the cursor follows the entry if it stays in this directory
+                self.selected_name = to.file_name().map(|name| name.to_owned());
+            }
+            Command::OpenWith { command, terminal } => {
+                let file = self
+                    .selected_file_in_table(file_manager)
+                    .ok_or(Error::Command("there is no entry to open".to_owned()))?;
+                let mut files = file_manager.selection();
+                if files.is_empty() {
+                    files.push(file.clone());
+                }
+                let launch =
+                    opener::launch(&command, terminal, &file, &files).map_err(Error::Command)?;
+                return Ok(self.launch(launch));
+            }
+            Command::Shell { command, attached } => {
+                //without a selection the entry under the cursor is used
+                let mut selection = file_manager.selection();
+                if selection.is_empty() {
+                    selection.extend(self.selected_file_in_table(file_manager));
+                }
+                let script = shell::expand(&command, &selection, file_manager.current_dir());
+                if attached {
+                    return Ok(AppEvents::RunInTerminal(shell::attached(
+                        &script,
+                        file_manager.current_dir(),
+                    )));
+                }
+                file_manager.run_captured(command, script);
+            }
+            Command::Run(name) => {
+                let action = self
+                    .config
+                    .actions
+                    .iter()
+                    .find(|action| action.name == name)
+                    .ok_or_else(|| {
+                        Error::Command(format!("there is no custom action \"{}\"", name))
+                    })?;
+                let command = Command::Shell {
+                    command: action.command.clone(),
+                    attached: action.attached,
+                };
+                return self.execute(command, file_manager);
+            }
+            Command::Action(action) => return Ok(self.run_action(action, file_manager)),
+        }
+        Ok(AppEvents::None)
+    }
+
+    /// move the cursor by delta entries, stopping at the first and last entry
+    fn move_cursor(&mut self, delta: isize, file_manager: &FileManager) {
+        let last = file_manager.num_files.saturating_sub(1);
+        let selected = self.table_state.selected().unwrap_or(0);
+        let new_index = selected.saturating_add_signed(delta).min(last);
+        self.table_state.select(Some(new_index));
+    }
+}
+
+impl MessageReceiver for ExplorerTable {
+    fn handle_message(
+        &mut self,
+        message: Option<Message>,
+        file_manager: &mut crate::file_manager::FileManager,
+    ) -> AppEvents {
+        //a prompt is answered once
+        match std::mem::replace(&mut self.message_source, MessageSource::None) {
+            MessageSource::DeletionConfirmationPrompt => {
+                if let Some(Message::Bool(true)) = message {
+                    return ExplorerTable::delete_selection(file_manager);
+                }
+            }
+            MessageSource::PasteConfirmationPrompt => {
+                if let Some(Message::Bool(true)) = message {
+                    return ExplorerTable::paste(file_manager);
+                }
+            }
+            MessageSource::None => {}
+        }
+        AppEvents::None
+    }
+}
+impl MessageSender for ExplorerTable {
+    fn get_message(&mut self) -> Option<Message> {
+        self.message.take()
+    }
+}
+
+impl State for ExplorerTable {
+    fn enter(&mut self, file_manager: &mut FileManager) {
+        file_manager.update();
+    }
+
+    fn exit(&mut self, _file_manager: &mut FileManager) {}
+
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        self.pending_keys.push(KeyChord::from(key_event));
+        let mut key_match = self.keymap.lookup(&self.pending_keys);
+        //an unfinished sequence doesn't swallow the next key, e.g. <g j> still moves down
+        if let KeyMatch::None = key_match
+            && self.pending_keys.len() > 1
+        {
+            self.pending_keys.drain(..self.pending_keys.len() - 1);
+            key_match = self.keymap.lookup(&self.pending_keys);
+        }
+        match key_match {
+            KeyMatch::Prefix => AppEvents::None,
+            KeyMatch::Action(action) => {
+                self.pending_keys.clear();
+                self.run_action(action, file_manager)
+            }
+            KeyMatch::None => {
+                self.pending_keys.clear();
+                AppEvents::None
+            }
+        }
+    }
+
+    fn run_action(&mut self, action: Action, file_manager: &mut FileManager) -> AppEvents {
+        let half_page = (self.viewport_height / 2).max(1) as isize;
+        if action.is_destructive() && file_manager.read_only {
+            return AppEvents::OpenErrorPopup(Error::Refused(format!(
+                "{} is disabled while choosing files",
+                action.name()
+            )));
+        }
+        match action {
+            Action::Quit => return AppEvents::Exit,
+            Action::Choose => {
+                return match self.chooser {
+                    Some(mode) => self.choose(mode, file_manager),
+                    None => AppEvents::None,
+                };
+            }
+            Action::OpenSorting => return AppEvents::OpenSortingPopupWindow,
+            Action::Help => return AppEvents::OpenKeyMappingPopupWindow,
+            Action::CommandPalette => return AppEvents::OpenCommandPalette,
+            Action::CommandLine => return AppEvents::OpenCommandLine,
+            Action::ShellPrompt => return AppEvents::OpenShellPrompt,
+            Action::CancelCommands => file_manager.cancel_commands(),
+            Action::ErrorLog => return AppEvents::OpenErrorLog,
+            Action::Custom(index) => {
+                let action = match self.config.actions.get(index) {
+                    Some(action) => action,
+                    None => {
+                        return AppEvents::OpenErrorPopup(Error::Command(format!(
+                            "there is no custom action {}, config.toml has {}",
+                            index,
+                            self.config.actions.len()
+                        )));
+                    }
+                };
+                let command = Command::Shell {
+                    command: action.command.clone(),
+                    attached: action.attached,
+                };
+                return self.run_command(command, file_manager);
+            }
+            //the listing is read again once the shell exits
+            Action::Shell => {
+                return AppEvents::RunInTerminal(shell::subshell(file_manager.current_dir()));
+            }
+            Action::OpenWith => {
+                return match self.selected_file_in_table(file_manager) {
+                    Some(path) => {
+                        self.message = Some(Message::Path(path));
+                        AppEvents::OpenOpenWithPopup
+                    }
+                    None => AppEvents::None,
+                };
+            }
+            Action::SortBy(sorting) => file_manager.sort(sorting),
+            Action::NewFile => return AppEvents::OpenNewFilePopup,
+            Action::OpenColumns => return AppEvents::OpenColumnsPopup,
+            Action::DiskUsage => return AppEvents::ChangeToDiskUsageWindow,
+            Action::CycleDirSorting => {
+                match file_manager.dir_sorting {
+                    SortDir::Unsorted => file_manager.dir_sorting = SortDir::Start,
+                    SortDir::Start => file_manager.dir_sorting = SortDir::End,
+                    SortDir::End => file_manager.dir_sorting = SortDir::Unsorted,
+                }
+                file_manager.update();
+            }
+            Action::MoveDown => {
+                let selected = self.table_state.selected().unwrap_or(0);
+                if file_manager.num_files <= selected + 1 {
+                    self.table_state.select_first();
+                } else {
+                    self.table_state.select(Some(selected + 1));
+                }
+            }
+            Action::MoveUp => {
+                let selected = self.table_state.selected().unwrap_or(0);
+                if selected == 0 {
+                    let last = file_manager.num_files.saturating_sub(1);
+                    self.table_state.select(Some(last));
+                } else {
+                    self.table_state.select(Some(selected - 1));
+                }
+            }
+            Action::PageDown => self.move_cursor(self.viewport_height as isize, file_manager),
+            Action::PageUp => self.move_cursor(-(self.viewport_height as isize), file_manager),
+            Action::HalfPageDown => self.move_cursor(half_page, file_manager),
+            Action::HalfPageUp => self.move_cursor(-half_page, file_manager),
+            Action::First => self.table_state.select_first(),
+            Action::Last => {
+                let last = file_manager.num_files.saturating_sub(1);
+                self.table_state.select(Some(last));
+            }
+            Action::EnterDir => return self.open_selected(file_manager),
+            Action::ParentDir => {
+                //put the cursor on the directory we came from
+                self.selected_name = file_manager.current_dir().file_name().map(|n| n.to_owned());
+                if let Err(e) = file_manager.change_dir(PathBuf::from("..")) {
+                    return AppEvents::OpenErrorPopup(e);
+                }
+                if self.table_state.selected().is_none() {
+                    self.table_state.select(Some(0));
+                }
+            }
+            Action::ToggleSelection => {
+                let path = match self.selected_file_in_table(file_manager) {
+                    None => return AppEvents::None,
+                    Some(path) => path,
+                };
+                let toggled = if file_manager.is_selected(&path) {
+                    file_manager.remove_from_selection(path)
+                } else {
+                    file_manager.add_to_selection(path)
+                };
+                if let Err(e) = toggled {
+                    return AppEvents::OpenErrorPopup(e);
+                }
+            }
+            Action::ClearSelection => file_manager.clear_selection(),
+            Action::Paste => {
+                let conflicts = file_manager.paste_conflicts();
+                if !self.config.confirm.overwrite || conflicts == 0 {
+                    return ExplorerTable::paste(file_manager);
+                }
+                self.message_source = MessageSource::PasteConfirmationPrompt;
+                self.message = Some(Message::String(format!(
+                    "{} of the selected entries already exist here, files will be overwritten and folders merged, are you sure?",
+                    conflicts
+                )));
+                return AppEvents::OpenConfirmationPopup;
+            }
+            Action::Delete => {
+                if !self.config.confirm.delete {
+                    return ExplorerTable::delete_selection(file_manager);
+                }
+                self.message_source = MessageSource::DeletionConfirmationPrompt;
+                self.message = Some(Message::String(
+                    "The selected files will be moved to the trash, are you sure?".to_owned(),
+                ));
+                return AppEvents::OpenConfirmationPopup;
+            }
+            Action::CycleSizeFormat => {
+                file_manager.size_format = match file_manager.size_format {
+                    SizeFormat::Iec => SizeFormat::Si,
+                    SizeFormat::Si => SizeFormat::Bytes,
+                    SizeFormat::Bytes => SizeFormat::Iec,
+                };
+            }
+            //calculate the sizes of all directories in the background
+            Action::CalculateDirSizes => file_manager.calculate_dir_sizes(),
+            Action::ToggleHidden => {
+                file_manager.show_hidden = !file_manager.show_hidden;
+                file_manager.update();
+            }
+        }
+        AppEvents::None
+    }
+
+    fn run_command(&mut self, command: Command, file_manager: &mut FileManager) -> AppEvents {
+        match self.execute(command, file_manager) {
+            Ok(app_event) => app_event,
+            Err(e) => AppEvents::OpenErrorPopup(e),
+        }
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.move_cursor(SCROLL_LINES, file_manager),
+            MouseEventKind::ScrollUp => self.move_cursor(-SCROLL_LINES, file_manager),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (x, y) = (mouse_event.column, mouse_event.row);
+                if !self.area.contains(Position::new(x, y)) {
+                    return AppEvents::None;
+                }
+                //the first line is the border, the second one the header
+                let header_y = self.area.y + 1;
+                if y == header_y {
+                    self.sort_by_column_at(x, file_manager);
+                    return AppEvents::None;
+                }
+                if y <= header_y || y + 1 >= self.area.bottom() {
+                    return AppEvents::None;
+                }
+                let index = self.offset + (y - header_y - 1) as usize;
+                if index >= file_manager.num_files {
+                    return AppEvents::None;
+                }
+                self.table_state.select(Some(index));
+                let double_click = matches!(self.last_click,
+                    Some((time, last_index)) if last_index == index && time.elapsed() <= DOUBLE_CLICK_TIME);
+                if double_click {
+                    self.last_click = None;
+                    return self.open_selected(file_manager);
+                } else {
+                    self.last_click = Some((Instant::now(), index));
+                }
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        self.render(frame.area(), frame.buffer_mut(), file_manager);
+    }
+}
+
+/// ExplorerTable as a ratatui widget, so other programs can draw the explorer into any area.
+/// Keys and mouse events are passed to the ExplorerTable through its State methods.
+/// The AppEvents they return ask the Controller for popups, without it the program handles
+/// the ones it supports: OpenConfirmationPopup with take_prompt and confirm, OpenErrorPopup
+/// carries the error, RunInTerminal the command. The others can be ignored
+pub struct ExplorerView<'a> {
+    file_manager: &'a FileManager,
+}
+
+impl<'a> ExplorerView<'a> {
+    pub fn new(file_manager: &'a FileManager) -> ExplorerView<'a> {
+        ExplorerView { file_manager }
+    }
+}
+
+impl StatefulWidget for ExplorerView<'_> {
+    type State = ExplorerTable;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ExplorerTable) {
+        state.render(area, buf, self.file_manager);
+    }
+}
+
+impl ExplorerTable {
+    /// draws the listing and the preview into area
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer, file_manager: &FileManager) {
+        let title = if file_manager.is_loading() {
+            Line::from(format!(
+                "FILE EXPLORER loading… ({})",
+                file_manager.num_files
+            ))
+        } else {
+            Line::from("FILE EXPLORER")
+        };
+        let help_text = match self.chooser {
+            Some(ChooseMode::Files) => Line::from("Choose files:<Enter> Key Mappings:<m>"),
+            Some(ChooseMode::Dir) => Line::from("Choose folder:<Enter> Key Mappings:<m>"),
+            None => Line::from("Key Mappings:<m>"),
+        };
+        let theme = &file_manager.theme;
+        let block = Block::bordered()
+            .title(title.left_aligned().style(theme.title))
+            .border_set(border::THICK)
+            .border_style(theme.border)
+            .title_bottom(help_text.right_aligned().style(theme.title));
+        let columns = &file_manager.visible_columns;
+        let header = Row::new(columns.iter().map(|c| c.title())).style(theme.header);
+
+        let (area, preview_area) = if self.config.preview.enabled {
+            let [table_area, preview_area] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(area);
+            (table_area, Some(preview_area))
+        } else {
+            (area, None)
+        };
+        //only the entries in the viewport are turned into rows, borders and header take 3 lines
+        self.viewport_height = (area.height.saturating_sub(3) as usize).max(1);
+        let entries = file_manager.get_entries();
+        //while a directory is still loading, the entry might just not have arrived yet
+        let mut keep_name = false;
+        if file_manager.listing_version() != self.listing_version {
+            self.listing_version = file_manager.listing_version();
+            if let Some(name) = &self.selected_name {
+                match entries.iter().position(|e| &e.name == name) {
+                    Some(index) => self.table_state.select(Some(index)),
+                    None => keep_name = file_manager.is_loading(),
+                }
+            }
+        }
+        let selected = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .min(entries.len().saturating_sub(1));
+        self.table_state.select(Some(selected));
+        if !keep_name && let Some(entry) = entries.get(selected) {
+            self.selected_name = Some(entry.name.clone());
+        }
+        if selected < self.offset {
+            self.offset = selected;
+        } else if selected >= self.offset + self.viewport_height {
+            self.offset = selected + 1 - self.viewport_height;
+        }
+        self.offset = self
+            .offset
+            .min(entries.len().saturating_sub(self.viewport_height));
+        let end = (self.offset + self.viewport_height).min(entries.len());
+
+        let mut rows: Vec<Row> = Vec::with_capacity(end - self.offset);
+        for entry in &entries[self.offset..end] {
+            //show the error instead of the columns, the entry might have vanished
+            if let Some(error) = &entry.error {
+                let name = entry.name.to_string_lossy();
+                rows.push(Row::new(vec![format!("{} <{}>", name, error)]).style(theme.error));
+                continue;
+            }
+            let row_strings: Vec<String> = columns
+                .iter()
+                .map(|c| c.cell(entry, file_manager))
+                .collect();
+            let mut style = theme.entry_style(entry);
+            if file_manager.is_selected(&entry.path) {
+                style = style.patch(theme.selection);
+            }
+            rows.push(Row::new(row_strings).style(style));
+        }
+        let widths: Vec<Constraint> = columns.iter().map(|c| c.width()).collect();
+        //the same layout the table uses for its columns
+        let column_rects = Layout::horizontal(widths.clone())
+            .spacing(1)
+            .split(area.inner(Margin::new(1, 1)));
+        self.column_areas = columns
+            .iter()
+            .copied()
+            .zip(column_rects.iter().copied())
+            .collect();
+        self.area = area;
+
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .cell_highlight_style(theme.cursor);
+
+        let mut visible_state = TableState::new()
+            .with_selected(Some(selected - self.offset))
+            .with_selected_column(self.table_state.selected_column());
+        StatefulWidget::render(table, area, buf, &mut visible_state);
+        if let Some(preview_area) = preview_area {
+            self.draw_preview(buf, preview_area, file_manager);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+pub mod columns_popup;
+pub mod command_line_popup;
+pub mod command_palette_popup;
+pub mod confirmation_popup;
+pub mod error_log_popup;
+pub mod error_popup;
+pub mod key_mapping_popup;
+pub mod new_file_popup;
+pub mod open_with_popup;
+pub mod output_popup;
+pub mod shell_prompt_popup;
+pub mod sorting_popup;
+pub mod text_field_popup;
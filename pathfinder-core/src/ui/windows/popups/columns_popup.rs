@@ -0,0 +1,115 @@
+use crate::column::Column;
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Margin, Rect};
+use ratatui::widgets::{Block, Clear, List, ListState};
+
+///This popup toggles the visible columns of the explorer table.
+///The name column is always shown, so it is not listed
+pub struct ColumnsPopup {
+    list_state: ListState,
+    ///where the list items were drawn, used to map mouse clicks to items
+    items_area: Rect,
+}
+
+impl ColumnsPopup {
+    pub fn new(
+        message: Option<crate::ui::message::Message>,
+        file_manager: &mut FileManager,
+    ) -> ColumnsPopup {
+        let mut popup = ColumnsPopup {
+            list_state: ListState::default(),
+            items_area: Rect::default(),
+        };
+        popup.list_state.select(Some(0));
+        popup.handle_message(message, file_manager);
+        popup
+    }
+
+    fn toggleable_columns() -> impl Iterator<Item = Column> {
+        Column::ALL.into_iter().filter(|c| *c != Column::Name)
+    }
+
+    fn selected_column(&self) -> Option<Column> {
+        let index = self.list_state.selected()?;
+        ColumnsPopup::toggleable_columns().nth(index)
+    }
+}
+
+impl MessageReceiver for ColumnsPopup {}
+impl MessageSender for ColumnsPopup {}
+
+impl State for ColumnsPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(column) = self.selected_column() {
+                    file_manager.toggle_column(column);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return AppEvents::ClosePopUp,
+            _ => {}
+        };
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let (x, y) = (mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => self.list_state.select_previous(),
+            MouseEventKind::ScrollDown => self.list_state.select_next(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let offset = self.list_state.offset();
+                let index = util::list_index_at(self.items_area, offset, x, y);
+                if let Some(index) = index
+                    && let Some(column) = ColumnsPopup::toggleable_columns().nth(index)
+                {
+                    self.list_state.select(Some(index));
+                    file_manager.toggle_column(column);
+                }
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let area = frame.area();
+
+        let popup_block = Block::bordered().title("columns:");
+        let mut popup_area = util::popup_area(area, 20, 50);
+
+        let items: Vec<String> = ColumnsPopup::toggleable_columns()
+            .map(|column| {
+                let check = if file_manager.visible_columns.contains(&column) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                format!("{} {}", check, column.title())
+            })
+            .collect();
+        let list = List::new(items)
+            .block(popup_block)
+            .highlight_style(file_manager.theme.popup_highlight);
+
+        popup_area.height = popup_area.height.min(list.len() as u16 + 2);
+        self.items_area = popup_area.inner(Margin::new(1, 1));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.list_state);
+    }
+}
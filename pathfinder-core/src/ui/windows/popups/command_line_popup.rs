@@ -0,0 +1,230 @@
+use crate::command::{self, Command, CommandHistory};
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+
+///A vim-like `:` line at the bottom of the screen.
+///The parsed command is passed to the window below, the line is added to the history
+pub struct CommandLinePopup {
+    line: String,
+    history: CommandHistory,
+    ///index of the history entry shown while browsing with Up and Down
+    history_index: Option<usize>,
+    ///what was typed before browsing the history, only entries starting with it are shown
+    typed: String,
+    ///byte index of the completed word, the candidates and the one that is shown
+    completion: Option<(usize, Vec<String>, usize)>,
+    ///why the line couldn't be parsed, shown until the line changes
+    error: Option<String>,
+    chosen: Option<Command>,
+}
+
+impl CommandLinePopup {
+    pub fn new() -> CommandLinePopup {
+        CommandLinePopup::with_history(CommandHistory::load())
+    }
+
+    pub fn with_history(history: CommandHistory) -> CommandLinePopup {
+        CommandLinePopup {
+            line: String::new(),
+            history,
+            history_index: None,
+            typed: String::new(),
+            completion: None,
+            error: None,
+            chosen: None,
+        }
+    }
+
+    fn submit(&mut self) -> AppEvents {
+        if self.line.trim().is_empty() {
+            return AppEvents::ClosePopUp;
+        }
+        //mistyped lines are kept as well, so they can be fixed from the history later
+        self.history.push(&self.line);
+        let saved = self.history.save();
+        match Command::parse(&self.line) {
+            Ok(command) => self.chosen = Some(command),
+            Err(e) => self.error = Some(e),
+        }
+        match saved {
+            //the command still runs once the error is dismissed, see handle_message
+            Err(e) => AppEvents::OpenErrorPopup(e),
+            Ok(()) if self.chosen.is_some() => AppEvents::ClosePopUp,
+            Ok(()) => AppEvents::None,
+        }
+    }
+
+    ///Tab and BackTab cycle through the completions of the last word
+    fn complete(&mut self, forward: bool) {
+        match &mut self.completion {
+            Some((_, candidates, index)) => {
+                let len = candidates.len();
+                *index = if forward {
+                    (*index + 1) % len
+                } else {
+                    (*index + len - 1) % len
+                };
+            }
+            None => {
+                let (start, candidates) = command::complete(&self.line);
+                if candidates.is_empty() {
+                    return;
+                }
+                let index = if forward { 0 } else { candidates.len() - 1 };
+                self.completion = Some((start, candidates, index));
+            }
+        }
+        if let Some((start, candidates, index)) = &self.completion {
+            self.line.truncate(*start);
+            self.line.push_str(&candidates[*index]);
+            //a single candidate is final, the next Tab completes the word after it
+            if candidates.len() == 1 {
+                self.completion = None;
+            }
+        }
+    }
+
+    ///Up and Down show older and newer history entries that start with what was typed
+    fn browse_history(&mut self, older: bool) {
+        let entries = self.history.entries();
+        if self.history_index.is_none() {
+            self.typed = self.line.clone();
+        }
+        let matches = |index: &usize| entries[*index].starts_with(&self.typed);
+        let next = match (self.history_index, older) {
+            (None, true) => (0..entries.len()).rev().find(matches),
+            (Some(current), true) => (0..current).rev().find(matches),
+            (Some(current), false) => (current + 1..entries.len()).find(matches),
+            (None, false) => return,
+        };
+        match next {
+            Some(index) => {
+                self.history_index = Some(index);
+                self.line = entries[index].clone();
+            }
+            //going past the newest entry shows the typed text again
+            None if !older => {
+                self.history_index = None;
+                self.line = self.typed.clone();
+            }
+            None => {}
+        }
+    }
+}
+
+impl Default for CommandLinePopup {
+    fn default() -> CommandLinePopup {
+        CommandLinePopup::new()
+    }
+}
+
+impl MessageReceiver for CommandLinePopup {
+    fn handle_message(
+        &mut self,
+        _message: Option<Message>,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        //the error popup about the history was closed
+        if self.chosen.is_some() {
+            AppEvents::ClosePopUp
+        } else {
+            AppEvents::None
+        }
+    }
+}
+impl MessageSender for CommandLinePopup {
+    fn get_message(&mut self) -> Option<Message> {
+        self.chosen.take().map(Message::Command)
+    }
+}
+
+impl State for CommandLinePopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        if !matches!(key_event.code, KeyCode::Tab | KeyCode::BackTab) {
+            self.completion = None;
+        }
+        if !matches!(key_event.code, KeyCode::Up | KeyCode::Down) {
+            self.history_index = None;
+        }
+        match key_event.code {
+            KeyCode::Esc => return AppEvents::ClosePopUp,
+            KeyCode::Char('c') if control => return AppEvents::ClosePopUp,
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Tab => self.complete(true),
+            KeyCode::BackTab => self.complete(false),
+            KeyCode::Up => self.browse_history(true),
+            KeyCode::Down => self.browse_history(false),
+            //like in vim, deleting the empty line leaves the command line
+            KeyCode::Backspace if self.line.is_empty() => return AppEvents::ClosePopUp,
+            KeyCode::Backspace => {
+                self.line.pop();
+            }
+            KeyCode::Char('u') if control => self.line.clear(),
+            KeyCode::Char('w') if control => {
+                let trimmed = self.line.trim_end().len();
+                let start = self.line[..trimmed].rfind(' ').map_or(0, |i| i + 1);
+                self.line.truncate(start);
+            }
+            KeyCode::Char(c) if !control => self.line.push(c),
+            _ => return AppEvents::None,
+        }
+        self.error = None;
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let area = frame.area();
+        if area.height == 0 {
+            return;
+        }
+        let line_area = Rect::new(area.x, area.bottom() - 1, area.width, 1);
+        //the line above shows the completions or the error
+        let info = match (&self.error, &self.completion) {
+            (Some(error), _) => Some(Line::styled(error.as_str(), theme.error)),
+            (None, Some((_, candidates, index))) => {
+                let spans: Vec<Span> = candidates
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, candidate)| {
+                        let style = if i == *index {
+                            theme.popup_highlight
+                        } else {
+                            Style::new()
+                        };
+                        [Span::styled(candidate.as_str(), style), Span::raw("  ")]
+                    })
+                    .collect();
+                Some(Line::from(spans))
+            }
+            (None, None) => None,
+        };
+        if let Some(info) = info
+            && area.height > 1
+        {
+            let info_area = Rect::new(area.x, line_area.y - 1, area.width, 1);
+            frame.render_widget(Clear, info_area);
+            frame.render_widget(Paragraph::new(info), info_area);
+        }
+
+        frame.render_widget(Clear, line_area);
+        frame.render_widget(Paragraph::new(format!(":{}", self.line)), line_area);
+        let cursor_x = line_area.x + 1 + self.line.chars().count() as u16;
+        frame.set_cursor_position((
+            cursor_x.min(line_area.right().saturating_sub(1)),
+            line_area.y,
+        ));
+    }
+}
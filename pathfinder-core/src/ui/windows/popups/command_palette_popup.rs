@@ -0,0 +1,172 @@
+use crate::file_manager::FileManager;
+use crate::keymap::{Action, KeyMap};
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph};
+use std::rc::Rc;
+
+///Lists every action with its key bindings, filtered by fuzzy matching the typed text.
+///The chosen action is passed to the window below
+pub struct CommandPalettePopup {
+    ///every action with its description and key bindings
+    items: Vec<(Action, String, String)>,
+    ///indices into items that match the query, best match first
+    matches: Vec<usize>,
+    query: String,
+    list_state: ListState,
+    chosen: Option<Action>,
+    ///where the list items were drawn, used to map mouse clicks to items
+    items_area: Rect,
+}
+
+impl CommandPalettePopup {
+    pub fn new(keymap: Rc<KeyMap>) -> CommandPalettePopup {
+        let items = Action::all()
+            .filter(|action| *action != Action::CommandPalette)
+            .map(|action| {
+                let keys = keymap.keys_for(action).join("/");
+                (action, action.description(), keys)
+            })
+            .collect();
+        let mut popup = CommandPalettePopup {
+            items,
+            matches: Vec::new(),
+            query: String::new(),
+            list_state: ListState::default(),
+            chosen: None,
+            items_area: Rect::default(),
+        };
+        popup.update_matches();
+        popup
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (action, description, _))| {
+                //the name is matched as well, so `sort_by mtime` finds the sort modes
+                let text = format!("{} {}", description, action.name());
+                util::fuzzy_score(&self.query, &text).map(|score| (score, index))
+            })
+            .collect();
+        //the sort is stable, equally good matches keep their order
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.list_state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn choose(&mut self, index: usize) -> AppEvents {
+        match self.matches.get(index) {
+            Some(item) => {
+                self.chosen = Some(self.items[*item].0);
+                AppEvents::ClosePopUp
+            }
+            None => AppEvents::None,
+        }
+    }
+}
+
+impl MessageReceiver for CommandPalettePopup {}
+impl MessageSender for CommandPalettePopup {
+    fn get_message(&mut self) -> Option<Message> {
+        self.chosen.take().map(Message::Action)
+    }
+}
+
+impl State for CommandPalettePopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Esc => return AppEvents::ClosePopUp,
+            KeyCode::Enter => return self.choose(self.list_state.selected().unwrap_or(0)),
+            KeyCode::Down | KeyCode::Tab => self.list_state.select_next(),
+            KeyCode::Up | KeyCode::BackTab => self.list_state.select_previous(),
+            KeyCode::Char('n') if control => self.list_state.select_next(),
+            KeyCode::Char('p') if control => self.list_state.select_previous(),
+            KeyCode::Backspace => {
+                if control {
+                    self.query.clear();
+                } else {
+                    self.query.pop();
+                }
+                self.update_matches();
+            }
+            KeyCode::Char(c) if !control => {
+                self.query.push(c);
+                self.update_matches();
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.list_state.select_next(),
+            MouseEventKind::ScrollUp => self.list_state.select_previous(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let offset = self.list_state.offset();
+                let (x, y) = (mouse_event.column, mouse_event.row);
+                if let Some(index) = util::list_index_at(self.items_area, offset, x, y) {
+                    return self.choose(index);
+                }
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let popup_area = util::popup_area(frame.area(), 60, 60);
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(popup_area);
+
+        let input = Paragraph::new(self.query.as_str())
+            .block(Block::bordered().title(Line::from("command:").style(theme.title)));
+
+        let items: Vec<Line> = self
+            .matches
+            .iter()
+            .map(|index| {
+                let (_, description, keys) = &self.items[*index];
+                Line::from(vec![
+                    Span::raw(description.as_str()),
+                    Span::raw("  "),
+                    Span::styled(keys.as_str(), theme.header),
+                ])
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered())
+            .highlight_style(theme.popup_highlight);
+
+        self.items_area = list_area.inner(Margin::new(1, 1));
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(input, input_area);
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+        frame.set_cursor_position((
+            input_area.x + 1 + self.query.chars().count() as u16,
+            input_area.y + 1,
+        ));
+    }
+}
@@ -1,15 +1,14 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
-    layout::{Constraint, Layout},
-    style::{Style, Stylize},
+    layout::{Constraint, Layout, Rect},
     text::Line,
     widgets::{Block, Clear, List, ListState, Paragraph, Wrap},
 };
 
 use crate::{
-    controller::{AppEvents, State},
     file_manager::FileManager,
-    message::{Message, MessageReceiver, MessageSender},
+    ui::controller::{AppEvents, State},
+    ui::message::{Message, MessageReceiver, MessageSender},
     util,
 };
 
@@ -17,6 +16,8 @@ pub struct ConfirmationPopup {
     text: String,
     confirmation_result: Option<bool>,
     list_state: ListState,
+    ///where the No/Yes items were drawn, used to map mouse clicks to items
+    items_area: Rect,
 }
 
 impl ConfirmationPopup {
@@ -25,6 +26,7 @@ impl ConfirmationPopup {
             text: "".to_owned(),
             confirmation_result: None,
             list_state: ListState::default(),
+            items_area: Rect::default(),
         };
         confirmation_popup.list_state.select(Some(0));
         confirmation_popup.handle_message(message, file_manager);
@@ -54,10 +56,29 @@ impl State for ConfirmationPopup {
         AppEvents::None
     }
 
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return AppEvents::None;
+        }
+        let index = util::list_index_at(self.items_area, 0, mouse_event.column, mouse_event.row);
+        match index {
+            Some(index @ (0 | 1)) => {
+                self.list_state.select(Some(index));
+                self.confirmation_result = Some(index == 1);
+                AppEvents::ClosePopUp
+            }
+            _ => AppEvents::None,
+        }
+    }
+
     fn draw(
         &mut self,
         frame: &mut ratatui::Frame,
-        _file_manager: &mut crate::file_manager::FileManager,
+        file_manager: &mut crate::file_manager::FileManager,
     ) {
         let area = frame.area();
 
@@ -82,8 +103,9 @@ impl State for ConfirmationPopup {
             Line::from("No").centered(),
             Line::from("Yes").centered(),
         ])
-        .highlight_style(Style::new().blue());
+        .highlight_style(file_manager.theme.popup_highlight);
 
+        self.items_area = list_inner;
         frame.render_widget(Clear, popup_area);
         frame.render_widget(popup_block_text, text_area);
         frame.render_widget(popup_block_selection, list_area);
@@ -97,10 +119,11 @@ impl MessageReceiver for ConfirmationPopup {
         &mut self,
         message: Option<Message>,
         _file_manager: &mut crate::file_manager::FileManager,
-    ) {
+    ) -> AppEvents {
         if let Some(Message::String(message)) = message {
             self.text = message;
         }
+        AppEvents::None
     }
 }
 impl MessageSender for ConfirmationPopup {
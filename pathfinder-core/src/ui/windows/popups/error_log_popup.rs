@@ -0,0 +1,119 @@
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph};
+use std::time::SystemTime;
+
+///lines moved per step of the scroll wheel
+const SCROLL_LINES: usize = 3;
+
+///Lists the errors of this session with the time they happened, the newest at the bottom
+pub struct ErrorLogPopup {
+    lines: Vec<String>,
+    ///index of the first visible line
+    scroll: usize,
+    ///number of lines that fit into the popup, updated on every draw
+    viewport_height: usize,
+}
+
+impl ErrorLogPopup {
+    pub fn new(errors: &[(SystemTime, String)]) -> ErrorLogPopup {
+        let mut lines: Vec<String> = errors
+            .iter()
+            .flat_map(|(time, text)| {
+                //toml errors span several lines, they are indented below the time
+                let time = util::format_time(*time);
+                let indent = " ".repeat(time.chars().count());
+                text.lines()
+                    .enumerate()
+                    .map(|(i, line)| match i {
+                        0 => format!("{}  {}", time, line),
+                        _ => format!("{}  {}", indent, line),
+                    })
+                    .collect::<Vec<String>>()
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push("<no errors>".to_owned());
+        }
+        ErrorLogPopup {
+            lines,
+            //the newest errors are the interesting ones, the first draw scrolls to them
+            scroll: usize::MAX,
+            viewport_height: 1,
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(self.viewport_height);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+}
+
+impl MessageReceiver for ErrorLogPopup {}
+impl MessageSender for ErrorLogPopup {}
+
+impl State for ErrorLogPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let page = self.viewport_height as isize;
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return AppEvents::ClosePopUp,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll_by(isize::MAX),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.scroll_by(SCROLL_LINES as isize),
+            MouseEventKind::ScrollUp => self.scroll_by(-(SCROLL_LINES as isize)),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let area = util::popup_area(frame.area(), 80, 80);
+        self.viewport_height = area.height.saturating_sub(2).max(1) as usize;
+        //the popup may have grown since the last scroll
+        self.scroll_by(0);
+        let position = format!(
+            "{}-{}/{}",
+            self.scroll + 1,
+            (self.scroll + self.viewport_height).min(self.lines.len()),
+            self.lines.len()
+        );
+        let block = Block::bordered()
+            .title(Line::from("ERRORS").style(theme.title))
+            .title_bottom(Line::from(position).right_aligned())
+            .border_style(theme.error);
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.viewport_height)
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
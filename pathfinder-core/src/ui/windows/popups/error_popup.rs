@@ -0,0 +1,60 @@
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::prelude::{Line, Stylize};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+
+///Shows an error until any key is pressed
+pub struct ErrorPopup {
+    text: String,
+}
+
+impl ErrorPopup {
+    pub fn new(text: String) -> ErrorPopup {
+        ErrorPopup { text }
+    }
+}
+
+impl MessageReceiver for ErrorPopup {}
+impl MessageSender for ErrorPopup {}
+
+impl State for ErrorPopup {
+    fn handle_key_event(
+        &mut self,
+        _key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        AppEvents::ClosePopUp
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::Down(_) => AppEvents::ClosePopUp,
+            _ => AppEvents::None,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let area = frame.area();
+        let popup_block = Block::bordered()
+            .title(Line::from("ERROR").bold())
+            .title_bottom(Line::from("press any key").right_aligned())
+            .style(file_manager.theme.error);
+        let popup_area = util::popup_area(area, 60, 40);
+
+        //toml errors point at the problem with a caret, so the text is not centered
+        let paragraph = Paragraph::new(self.text.as_str())
+            .wrap(Wrap { trim: false })
+            .block(popup_block);
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(paragraph, popup_area);
+    }
+}
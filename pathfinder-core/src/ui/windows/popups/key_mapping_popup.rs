@@ -0,0 +1,94 @@
+use crate::file_manager::FileManager;
+use crate::keymap::{Action, KeyMap};
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::Alignment::Center;
+use ratatui::widgets::{Block, Clear, List, ListState};
+use std::rc::Rc;
+
+///Lists every action with the keys it is bound to in the keymap
+pub struct KeyMappingPopup {
+    keymap: Rc<KeyMap>,
+    list_state: ListState,
+}
+
+impl KeyMappingPopup {
+    pub fn new(keymap: Rc<KeyMap>) -> KeyMappingPopup {
+        KeyMappingPopup {
+            keymap,
+            list_state: ListState::default(),
+        }
+    }
+}
+
+impl MessageReceiver for KeyMappingPopup {}
+impl MessageSender for KeyMappingPopup {}
+
+impl State for KeyMappingPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        //the list might not fit on small terminals
+        match key_event.code {
+            KeyCode::Down | KeyCode::Char('j') => *self.list_state.offset_mut() += 1,
+            KeyCode::Up | KeyCode::Char('k') => {
+                *self.list_state.offset_mut() = self.list_state.offset().saturating_sub(1)
+            }
+            _ => return AppEvents::ClosePopUp,
+        }
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => {
+                *self.list_state.offset_mut() += 1;
+                AppEvents::None
+            }
+            MouseEventKind::ScrollUp => {
+                *self.list_state.offset_mut() = self.list_state.offset().saturating_sub(1);
+                AppEvents::None
+            }
+            MouseEventKind::Down(_) => AppEvents::ClosePopUp,
+            _ => AppEvents::None,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let area = frame.area();
+
+        let popup_block = Block::bordered()
+            .title("KEY MAPPINGS")
+            .title_alignment(Center);
+        let mut popup_area = util::popup_area(area, 40, 30);
+
+        let mut items: Vec<String> = Action::all()
+            .filter_map(|action| {
+                let keys = self.keymap.keys_for(action);
+                if keys.is_empty() {
+                    return None;
+                }
+                Some(format!("{} → {}", keys.join("/"), action.description()))
+            })
+            .collect();
+        items.push("<click>/<double click> → move cursor/enter folder".to_owned());
+        items.push("<click on header> → sort by column".to_owned());
+        let list = List::new(items)
+            .block(popup_block)
+            .highlight_style(file_manager.theme.popup_highlight);
+
+        popup_area.height = (list.len() as u16 + 2).min(area.height);
+        popup_area.y = area.y + (area.height - popup_area.height) / 2;
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.list_state);
+    }
+}
@@ -3,14 +3,13 @@ use std::path::PathBuf;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
-    style::{Style, Stylize},
     widgets::{Block, Clear, List, ListState},
 };
 
 use crate::{
-    controller::{AppEvents, State},
     file_manager::FileManager,
-    message::{Message, MessageReceiver, MessageSender},
+    ui::controller::{AppEvents, State},
+    ui::message::{Message, MessageReceiver, MessageSender},
     util,
 };
 
@@ -32,32 +31,33 @@ impl NewFilePopup {
 impl MessageReceiver for NewFilePopup {
     fn handle_message(
         &mut self,
-        message: Option<crate::message::Message>,
+        message: Option<crate::ui::message::Message>,
         file_manager: &mut crate::file_manager::FileManager,
-    ) {
-        if let Some(Message::String(message)) = message {
-            let index = self.list_state.selected().unwrap();
-            let path = PathBuf::from(message);
-            match index {
-                0 => {
-                    let _ = file_manager.create_file(path);
-                }
-                1 => {
-                    let _ = file_manager.create_folder(path);
+    ) -> AppEvents {
+        let created = match message {
+            Some(Message::String(message)) => {
+                let path = PathBuf::from(message);
+                match self.list_state.selected() {
+                    Some(0) => file_manager.create_file(path),
+                    Some(1) => file_manager.create_folder(path),
+                    _ => Ok(()),
                 }
-                _ => {}
             }
-        }
+            _ => Ok(()),
+        };
         file_manager.update();
+        match created {
+            Ok(()) => AppEvents::None,
+            Err(e) => AppEvents::OpenErrorPopup(e),
+        }
     }
 }
 
 impl MessageSender for NewFilePopup {
     fn get_message(&mut self) -> Option<Message> {
-        let index = self.list_state.selected().unwrap();
-        match index {
-            0 => Some(Message::String(String::from("File"))),
-            1 => Some(Message::String(String::from("Folder"))),
+        match self.list_state.selected() {
+            Some(0) => Some(Message::String(String::from("File"))),
+            Some(1) => Some(Message::String(String::from("Folder"))),
             _ => None,
         }
     }
@@ -78,7 +78,7 @@ impl State for NewFilePopup {
         AppEvents::None
     }
 
-    fn draw(&mut self, frame: &mut Frame, _file_manager: &mut FileManager) {
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
         let area = frame.area();
 
         let popup_block = Block::bordered().title("Create:");
@@ -86,7 +86,7 @@ impl State for NewFilePopup {
 
         let list = List::new(vec!["File".to_owned(), "Folder".to_owned()])
             .block(popup_block)
-            .highlight_style(Style::new().red());
+            .highlight_style(file_manager.theme.popup_highlight);
 
         frame.render_widget(Clear, popup_area);
         frame.render_stateful_widget(list, popup_area, &mut self.list_state);
@@ -0,0 +1,193 @@
+use crate::command::Command;
+use crate::config::Config;
+use crate::file_manager::FileManager;
+use crate::opener::{self, Candidate};
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListState, Paragraph};
+use std::path::PathBuf;
+
+///Lists the programs that can open a file, the last item lets the user type a command.
+///The choice is passed to the window below as a Command::OpenWith
+pub struct OpenWithPopup {
+    name: String,
+    candidates: Vec<Candidate>,
+    list_state: ListState,
+    ///the typed command and if it runs in the terminal, Some while the user types one
+    custom: Option<(String, bool)>,
+    chosen: Option<Command>,
+    ///where the list items were drawn, used to map mouse clicks to items
+    items_area: Rect,
+}
+
+impl OpenWithPopup {
+    pub fn new(path: PathBuf, config: &Config) -> OpenWithPopup {
+        let candidates = opener::candidates(&path, config, &opener::application_dirs());
+        OpenWithPopup::with_candidates(path, candidates)
+    }
+
+    pub fn with_candidates(path: PathBuf, candidates: Vec<Candidate>) -> OpenWithPopup {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut popup = OpenWithPopup {
+            name,
+            candidates,
+            list_state: ListState::default(),
+            custom: None,
+            chosen: None,
+            items_area: Rect::default(),
+        };
+        popup.list_state.select(Some(0));
+        popup
+    }
+
+    fn choose(&mut self, index: usize) -> AppEvents {
+        match self.candidates.get(index) {
+            Some(candidate) => {
+                self.chosen = Some(Command::OpenWith {
+                    command: candidate.command.clone(),
+                    terminal: candidate.terminal,
+                });
+                AppEvents::ClosePopUp
+            }
+            //the item after the candidates
+            None if index == self.candidates.len() => {
+                //typed commands are mostly terminal programs, Tab switches
+                self.custom = Some((String::new(), true));
+                AppEvents::None
+            }
+            None => AppEvents::None,
+        }
+    }
+
+    fn handle_custom_key(&mut self, key_event: KeyEvent) -> AppEvents {
+        let (command, terminal) = match &mut self.custom {
+            Some(custom) => custom,
+            None => return AppEvents::None,
+        };
+        match key_event.code {
+            KeyCode::Esc => self.custom = None,
+            KeyCode::Tab => *terminal = !*terminal,
+            KeyCode::Backspace => {
+                command.pop();
+            }
+            KeyCode::Enter if !command.trim().is_empty() => {
+                self.chosen = Some(Command::OpenWith {
+                    command: command.clone(),
+                    terminal: *terminal,
+                });
+                return AppEvents::ClosePopUp;
+            }
+            KeyCode::Char(c) => command.push(c),
+            _ => {}
+        }
+        AppEvents::None
+    }
+}
+
+impl MessageReceiver for OpenWithPopup {}
+impl MessageSender for OpenWithPopup {
+    fn get_message(&mut self) -> Option<Message> {
+        self.chosen.take().map(Message::Command)
+    }
+}
+
+impl State for OpenWithPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        if self.custom.is_some() {
+            return self.handle_custom_key(key_event);
+        }
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => return AppEvents::ClosePopUp,
+            KeyCode::Enter | KeyCode::Char('l') => {
+                return self.choose(self.list_state.selected().unwrap_or(0));
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        if self.custom.is_some() {
+            return AppEvents::None;
+        }
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.list_state.select_next(),
+            MouseEventKind::ScrollUp => self.list_state.select_previous(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let offset = self.list_state.offset();
+                let (x, y) = (mouse_event.column, mouse_event.row);
+                if let Some(index) = util::list_index_at(self.items_area, offset, x, y) {
+                    return self.choose(index);
+                }
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let popup_area = util::popup_area(frame.area(), 60, 50);
+        let title = Line::from(format!("open {} with", self.name)).style(theme.title);
+
+        if let Some((command, terminal)) = &self.custom {
+            let [input_area, _] =
+                Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(popup_area);
+            let mode = if *terminal {
+                "in the terminal <tab>"
+            } else {
+                "in the background <tab>"
+            };
+            let input = Paragraph::new(command.as_str()).block(
+                Block::bordered().title(title).title_bottom(
+                    Line::from(format!("%f file, %F selection, {}", mode)).right_aligned(),
+                ),
+            );
+            frame.render_widget(Clear, input_area);
+            frame.render_widget(input, input_area);
+            frame.set_cursor_position((
+                input_area.x + 1 + command.chars().count() as u16,
+                input_area.y + 1,
+            ));
+            return;
+        }
+
+        let mut items: Vec<Line> = self
+            .candidates
+            .iter()
+            .map(|candidate| {
+                let mut spans = vec![Span::raw(candidate.name.as_str())];
+                if candidate.name != candidate.command {
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(candidate.command.as_str(), theme.header));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        items.push(Line::from("other command…"));
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(theme.popup_highlight);
+        self.items_area = popup_area.inner(Margin::new(1, 1));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.list_state);
+    }
+}
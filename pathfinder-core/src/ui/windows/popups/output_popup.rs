@@ -0,0 +1,123 @@
+use crate::file_manager::FileManager;
+use crate::shell::CommandOutput;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph};
+
+///lines moved per step of the scroll wheel
+const SCROLL_LINES: usize = 3;
+
+///Shows what a shell command printed, scrollable like a pager
+pub struct OutputPopup {
+    title: String,
+    status: String,
+    lines: Vec<String>,
+    ///index of the first visible line
+    scroll: usize,
+    ///number of lines that fit into the popup, updated on every draw
+    viewport_height: usize,
+}
+
+impl OutputPopup {
+    pub fn new(output: CommandOutput) -> OutputPopup {
+        let status = match output.status {
+            Ok(status) => match status.code() {
+                Some(code) => format!("exit {}", code),
+                //killed by a signal
+                None => status.to_string(),
+            },
+            Err(e) => e,
+        };
+        let mut lines: Vec<String> = output
+            .text
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect();
+        if lines.is_empty() {
+            lines.push("<no output>".to_owned());
+        }
+        if output.truncated {
+            lines.push("<truncated, the command was stopped>".to_owned());
+        }
+        OutputPopup {
+            title: output.command,
+            status,
+            lines,
+            scroll: 0,
+            viewport_height: 1,
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(self.viewport_height);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+}
+
+impl MessageReceiver for OutputPopup {}
+impl MessageSender for OutputPopup {}
+
+impl State for OutputPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let page = self.viewport_height as isize;
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return AppEvents::ClosePopUp,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll_by(isize::MAX),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match mouse_event.kind {
+            MouseEventKind::ScrollDown => self.scroll_by(SCROLL_LINES as isize),
+            MouseEventKind::ScrollUp => self.scroll_by(-(SCROLL_LINES as isize)),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let area = util::popup_area(frame.area(), 80, 80);
+        self.viewport_height = area.height.saturating_sub(2).max(1) as usize;
+        //the popup may have grown since the last scroll
+        self.scroll_by(0);
+        let position = format!(
+            "{}-{}/{}",
+            self.scroll + 1,
+            (self.scroll + self.viewport_height).min(self.lines.len()),
+            self.lines.len()
+        );
+        let block = Block::bordered()
+            .title(Line::from(format!("! {}", self.title)).style(theme.title))
+            .title_bottom(Line::from(self.status.as_str()))
+            .title_bottom(Line::from(position).right_aligned());
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(self.viewport_height)
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::command::Command;
+use crate::file_manager::FileManager;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{Message, MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph};
+
+///Asks for a shell command to run on the selection.
+///The command is passed to the window below as a Command::Shell
+pub struct ShellPromptPopup {
+    command: String,
+    ///run in the terminal instead of showing the output in a popup
+    attached: bool,
+    chosen: Option<Command>,
+}
+
+impl ShellPromptPopup {
+    pub fn new() -> ShellPromptPopup {
+        ShellPromptPopup {
+            command: String::new(),
+            attached: false,
+            chosen: None,
+        }
+    }
+}
+
+impl Default for ShellPromptPopup {
+    fn default() -> ShellPromptPopup {
+        ShellPromptPopup::new()
+    }
+}
+
+impl MessageReceiver for ShellPromptPopup {}
+impl MessageSender for ShellPromptPopup {
+    fn get_message(&mut self) -> Option<Message> {
+        self.chosen.take().map(Message::Command)
+    }
+}
+
+impl State for ShellPromptPopup {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Esc => return AppEvents::ClosePopUp,
+            KeyCode::Tab => self.attached = !self.attached,
+            KeyCode::Enter if self.command.trim().is_empty() => return AppEvents::ClosePopUp,
+            KeyCode::Enter => {
+                self.chosen = Some(Command::Shell {
+                    command: self.command.clone(),
+                    attached: self.attached,
+                });
+                return AppEvents::ClosePopUp;
+            }
+            KeyCode::Backspace => {
+                self.command.pop();
+            }
+            KeyCode::Char('u') if control => self.command.clear(),
+            KeyCode::Char(c) if !control => self.command.push(c),
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let theme = &file_manager.theme;
+        let [area] = Layout::vertical([Constraint::Length(3)])
+            .flex(Flex::Center)
+            .areas(util::popup_area(frame.area(), 60, 100));
+        let mode = if self.attached {
+            "in the terminal <tab>"
+        } else {
+            "output in a popup <tab>"
+        };
+        let block = Block::bordered()
+            .title(Line::from("!").style(theme.title))
+            .title_bottom(Line::from(format!("%s selection, %d folder, {}", mode)).right_aligned());
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(self.command.as_str()).block(block), area);
+        frame.set_cursor_position((area.x + 1 + self.command.chars().count() as u16, area.y + 1));
+    }
+}
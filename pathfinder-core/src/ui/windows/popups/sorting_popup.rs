@@ -0,0 +1,111 @@
+use crate::file_manager::FileManager;
+use crate::sorting::Sorting;
+use crate::ui::controller::{AppEvents, State};
+use crate::ui::message::{MessageReceiver, MessageSender};
+use crate::util;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Margin, Rect};
+use ratatui::widgets::{Block, Clear, List, ListState};
+
+pub struct SortingPopUp {
+    list_state: ListState,
+    ///where the list items were drawn, used to map mouse clicks to items
+    items_area: Rect,
+}
+
+impl SortingPopUp {
+    pub fn new(
+        message: Option<crate::ui::message::Message>,
+        file_manager: &mut FileManager,
+    ) -> SortingPopUp {
+        let mut popup = SortingPopUp {
+            list_state: ListState::default(),
+            items_area: Rect::default(),
+        };
+        //start on the active sort mode
+        let active = Sorting::modes().position(|s| s == file_manager.sorting());
+        popup.list_state.select(Some(active.unwrap_or(0)));
+        popup.handle_message(message, file_manager);
+        popup
+    }
+
+    fn selected_sort_mode(&self) -> Option<Sorting> {
+        let index = self.list_state.selected()?;
+        Sorting::modes().nth(index)
+    }
+}
+
+impl MessageReceiver for SortingPopUp {}
+impl MessageSender for SortingPopUp {}
+
+impl State for SortingPopUp {
+    fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Enter => match self.selected_sort_mode() {
+                None => return AppEvents::None,
+                Some(sorting) => {
+                    file_manager.sort(sorting);
+                    return AppEvents::ClosePopUp;
+                }
+            },
+            KeyCode::Esc => return AppEvents::ClosePopUp,
+            _ => {}
+        };
+        AppEvents::None
+    }
+
+    fn handle_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        file_manager: &mut FileManager,
+    ) -> AppEvents {
+        let (x, y) = (mouse_event.column, mouse_event.row);
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => self.list_state.select_previous(),
+            MouseEventKind::ScrollDown => self.list_state.select_next(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let offset = self.list_state.offset();
+                let index = util::list_index_at(self.items_area, offset, x, y);
+                if let Some(index) = index
+                    && let Some(sorting) = Sorting::modes().nth(index)
+                {
+                    self.list_state.select(Some(index));
+                    file_manager.sort(sorting);
+                    return AppEvents::ClosePopUp;
+                }
+            }
+            _ => {}
+        }
+        AppEvents::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, file_manager: &mut FileManager) {
+        let area = frame.area();
+
+        let active = file_manager.sorting();
+        let popup_block = Block::bordered().title(format!("sort by: ({})", active.label()));
+        let mut popup_area = util::popup_area(area, 25, 80);
+
+        let items: Vec<String> = Sorting::modes()
+            .map(|sorting| {
+                let marker = if sorting == active { "● " } else { "  " };
+                format!("{}{}", marker, sorting.label())
+            })
+            .collect();
+        let list = List::new(items)
+            .block(popup_block)
+            .highlight_style(file_manager.theme.popup_highlight);
+
+        popup_area.height = popup_area.height.min(list.len() as u16 + 2);
+        self.items_area = popup_area.inner(Margin::new(1, 1));
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(list, popup_area, &mut self.list_state);
+    }
+}
@@ -2,9 +2,9 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::widgets::{Block, Clear, Paragraph};
 
 use crate::{
-    controller::{AppEvents, State},
     file_manager::FileManager,
-    message::{Message, MessageReceiver, MessageSender},
+    ui::controller::{AppEvents, State},
+    ui::message::{Message, MessageReceiver, MessageSender},
 };
 
 ///This popup is for retrieving a String from the user.
@@ -28,10 +28,15 @@ impl TextFieldPopup {
 }
 
 impl MessageReceiver for TextFieldPopup {
-    fn handle_message(&mut self, message: Option<Message>, _file_manager: &mut FileManager) {
+    fn handle_message(
+        &mut self,
+        message: Option<Message>,
+        _file_manager: &mut FileManager,
+    ) -> AppEvents {
         if let Some(Message::String(message)) = message {
             self.title = message;
         }
+        AppEvents::None
     }
 }
 
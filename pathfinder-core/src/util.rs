@@ -0,0 +1,278 @@
+use ratatui::layout::{Constraint, Flex, Position, Rect};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical =
+        ratatui::layout::Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal =
+        ratatui::layout::Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// the directory of the config files, `$XDG_CONFIG_HOME/pathfinder` or `~/.config/pathfinder`
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("pathfinder"))
+}
+
+/// the directory of files the explorer writes, like the command history,
+/// `$XDG_STATE_HOME/pathfinder` or `~/.local/state/pathfinder`
+pub fn state_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/state"),
+    };
+    Some(base.join("pathfinder"))
+}
+
+/// matches text against a shell pattern with `*`, `?` and classes like `[a-z]` or `[!0-9]`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    //pattern position after the last star and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            backtrack = Some((p + 1, t));
+            p += 1;
+            continue;
+        }
+        if let Some(len) = glob_match_char(&pattern[p..], text[t]) {
+            p += len;
+            t += 1;
+            continue;
+        }
+        //let the last star swallow one more character
+        match backtrack {
+            Some((star_p, star_t)) => {
+                backtrack = Some((star_p, star_t + 1));
+                p = star_p;
+                t = star_t + 1;
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// the length of the start of pattern if it matches c
+fn glob_match_char(pattern: &[char], c: char) -> Option<usize> {
+    match pattern.first()? {
+        '?' => Some(1),
+        '[' => match glob_class_end(pattern) {
+            Some(end) => {
+                let negated = matches!(pattern.get(1), Some('!' | '^'));
+                let items = &pattern[if negated { 2 } else { 1 }..end];
+                let mut found = false;
+                let mut i = 0;
+                while i < items.len() {
+                    if i + 2 < items.len() && items[i + 1] == '-' {
+                        found |= items[i] <= c && c <= items[i + 2];
+                        i += 3;
+                    } else {
+                        found |= items[i] == c;
+                        i += 1;
+                    }
+                }
+                if found != negated {
+                    Some(end + 1)
+                } else {
+                    None
+                }
+            }
+            //an unclosed bracket is an ordinary character
+            None if c == '[' => Some(1),
+            None => None,
+        },
+        p if *p == c => Some(1),
+        _ => None,
+    }
+}
+
+/// index of the `]` closing the class at the start of pattern, a `]` right after the
+/// opening bracket belongs to the class
+fn glob_class_end(pattern: &[char]) -> Option<usize> {
+    let mut start = 1;
+    if matches!(pattern.get(start), Some('!' | '^')) {
+        start += 1;
+    }
+    if pattern.get(start) == Some(&']') {
+        start += 1;
+    }
+    let rest = pattern.get(start..)?;
+    rest.iter().position(|c| *c == ']').map(|pos| start + pos)
+}
+
+/// scores how well pattern matches text, None if the characters of pattern don't appear in
+/// text in the same order. Case and whitespace in the pattern are ignored.
+/// Consecutive characters and matches at the start of words score higher
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut last_match: Option<usize> = None;
+    for c in pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+    {
+        let index = (next..text.len()).find(|&i| text[i] == c)?;
+        score += 1;
+        if index > 0 && last_match == Some(index - 1) {
+            score += 5;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        //skipped characters make the match worse
+        score -= (index - next) as i64;
+        last_match = Some(index);
+        next = index + 1;
+    }
+    Some(score)
+}
+
+/// index of the list item at the given terminal position.
+/// items_area is the area the items are rendered in (without borders), offset the index of the first visible item
+pub fn list_index_at(items_area: Rect, offset: usize, column: u16, row: u16) -> Option<usize> {
+    if !items_area.contains(Position::new(column, row)) {
+        return None;
+    }
+    Some(offset + (row - items_area.y) as usize)
+}
+
+/// formats a unix mode like `ls -l` does, e.g. `drwxr-xr-x`
+pub fn format_permissions(mode: u32) -> String {
+    let type_char = match mode & 0o170000 {
+        0o040000 => 'd',
+        0o120000 => 'l',
+        0o020000 => 'c',
+        0o060000 => 'b',
+        0o010000 => 'p',
+        0o140000 => 's',
+        _ => '-',
+    };
+    let mut s = String::with_capacity(10);
+    s.push(type_char);
+    for (shift, special, special_char) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+        let bits = (mode >> shift) & 0o7;
+        s.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        s.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        let exec = bits & 0o1 != 0;
+        s.push(match (mode & special != 0, exec) {
+            (true, true) => special_char,
+            (true, false) => special_char.to_ascii_uppercase(),
+            (false, true) => 'x',
+            (false, false) => '-',
+        });
+    }
+    s
+}
+
+/// formats a point in time as local time `YYYY-MM-DD HH:MM`
+#[cfg(unix)]
+pub fn format_time(time: SystemTime) -> String {
+    let secs = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return String::new();
+    }
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min
+    )
+}
+
+/// looks up the user name for a uid, falls back to the numeric id
+#[cfg(unix)]
+pub fn user_name(uid: u32) -> String {
+    static CACHE: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(uid)
+        .or_insert_with(|| {
+            let mut buf = vec![0 as libc::c_char; 4096];
+            let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::passwd = std::ptr::null_mut();
+            let ret = unsafe {
+                libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if ret != 0 || result.is_null() {
+                return uid.to_string();
+            }
+            unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .clone()
+}
+
+/// looks up the group name for a gid, falls back to the numeric id
+#[cfg(unix)]
+pub fn group_name(gid: u32) -> String {
+    static CACHE: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(gid)
+        .or_insert_with(|| {
+            let mut buf = vec![0 as libc::c_char; 4096];
+            let mut grp: libc::group = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::group = std::ptr::null_mut();
+            let ret = unsafe {
+                libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result)
+            };
+            if ret != 0 || result.is_null() {
+                return gid.to_string();
+            }
+            unsafe { std::ffi::CStr::from_ptr(grp.gr_name) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .clone()
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum SizeFormat {
+    Bytes,
+    Iec,
+    Si,
+}
+
+/// formats a byte count, e.g. `1.5 KiB` (IEC) or `1.5 kB` (SI)
+pub fn format_size(bytes: u64, format: SizeFormat) -> String {
+    let (base, units) = match format {
+        SizeFormat::Bytes => return bytes.to_string(),
+        SizeFormat::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        SizeFormat::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= base && unit < units.len() - 1 {
+        size /= base;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, units[0])
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
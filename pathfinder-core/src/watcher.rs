@@ -0,0 +1,256 @@
+use crate::event_loop::WorkerMessage;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+/// after the last event the watcher waits this long for more events before it reports a change
+#[cfg(target_os = "linux")]
+const DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+/// if events keep coming (e.g. during a build), a change is reported at least this often
+#[cfg(target_os = "linux")]
+const MAX_DELAY: std::time::Duration = std::time::Duration::from_millis(1000);
+
+/// Watches the current directory and the previewed one with inotify on a background thread.
+/// Debounced changes are reported through the sender given to spawn
+pub struct DirWatcher {
+    watches: Arc<Mutex<Watches>>,
+}
+
+/// The inotify instance, shared with the thread. Watches are added by the caller while the
+/// thread can't read events, so no event of a new directory is attributed to the old one
+struct Watches {
+    /// -1 once the thread closed it
+    fd: i32,
+    /// watch descriptors, -1 if nothing is watched
+    dir: i32,
+    /// the watched directory, its changes are reported as paths below it
+    dir_path: PathBuf,
+    preview: i32,
+    preview_path: PathBuf,
+    /// the pending events of the previous directory and preview don't matter anymore
+    dir_switched: bool,
+    preview_switched: bool,
+    /// the DirWatcher is gone, the thread closes fd and stops
+    stopped: bool,
+}
+
+impl DirWatcher {
+    /// starts the watcher thread, returns None if inotify is not available
+    #[cfg(target_os = "linux")]
+    pub fn spawn(changed: Sender<WorkerMessage>) -> Option<DirWatcher> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return None;
+        }
+        let watches = Arc::new(Mutex::new(Watches {
+            fd,
+            dir: -1,
+            dir_path: PathBuf::new(),
+            preview: -1,
+            preview_path: PathBuf::new(),
+            dir_switched: false,
+            preview_switched: false,
+            stopped: false,
+        }));
+        let thread_watches = watches.clone();
+        std::thread::spawn(move || watch_loop(fd, thread_watches, changed));
+        Some(DirWatcher { watches })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn spawn(_changed: Sender<WorkerMessage>) -> Option<DirWatcher> {
+        None
+    }
+
+    /// watch path instead of the previously watched directory
+    pub fn watch(&self, path: &Path) {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+        let old = watches.dir;
+        watches.dir = watches.add(path);
+        watches.dir_path = path.to_path_buf();
+        watches.remove(old);
+        watches.dir_switched = true;
+    }
+
+    /// watch the file or directory shown in the preview, None if it shows nothing
+    pub fn watch_preview(&self, path: Option<&Path>) {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+        let old = watches.preview;
+        watches.preview = match path {
+            Some(path) => watches.add(path),
+            None => -1,
+        };
+        watches.preview_path = path.map(Path::to_path_buf).unwrap_or_default();
+        watches.remove(old);
+        watches.preview_switched = true;
+    }
+}
+
+impl Drop for DirWatcher {
+    fn drop(&mut self) {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+        watches.stopped = true;
+    }
+}
+
+impl Watches {
+    #[cfg(target_os = "linux")]
+    fn add(&self, path: &Path) -> i32 {
+        use std::os::unix::ffi::OsStrExt;
+        const MASK: u32 = libc::IN_CREATE
+            | libc::IN_DELETE
+            | libc::IN_MODIFY
+            | libc::IN_ATTRIB
+            | libc::IN_CLOSE_WRITE
+            | libc::IN_MOVED_FROM
+            | libc::IN_MOVED_TO
+            | libc::IN_DELETE_SELF
+            | libc::IN_MOVE_SELF;
+        if self.fd < 0 {
+            return -1;
+        }
+        match std::ffi::CString::new(path.as_os_str().as_bytes()) {
+            Ok(c_path) => unsafe { libc::inotify_add_watch(self.fd, c_path.as_ptr(), MASK) },
+            Err(_e) => -1,
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add(&self, _path: &Path) -> i32 {
+        -1
+    }
+
+    /// removes the watch wd unless it is still in use, inotify gives the same directory
+    /// (e.g. a link to the current one in the preview) the same descriptor
+    fn remove(&self, wd: i32) {
+        if wd < 0 || wd == self.dir || wd == self.preview || self.fd < 0 {
+            return;
+        }
+        #[cfg(target_os = "linux")]
+        unsafe {
+            libc::inotify_rm_watch(self.fd, wd)
+        };
+    }
+}
+
+/// When events of a watch are reported
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct Debounce {
+    first_event: Option<std::time::Instant>,
+    last_event: Option<std::time::Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl Debounce {
+    fn event(&mut self, now: std::time::Instant) {
+        self.first_event.get_or_insert(now);
+        self.last_event = Some(now);
+    }
+
+    /// true once the events calmed down or kept coming for too long
+    fn is_due(&mut self) -> bool {
+        let due = match (self.first_event, self.last_event) {
+            (Some(first), Some(last)) => last.elapsed() >= DEBOUNCE || first.elapsed() >= MAX_DELAY,
+            _ => false,
+        };
+        if due {
+            *self = Debounce::default();
+        }
+        due
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn watch_loop(fd: i32, watches: Arc<Mutex<Watches>>, changed: Sender<WorkerMessage>) {
+    use std::time::Instant;
+
+    const EVENT_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+
+    let mut dir_events = Debounce::default();
+    //what changed in the directory since the last report
+    let mut dir_changes: Vec<PathBuf> = Vec::new();
+    let mut preview_events = Debounce::default();
+    let mut preview_path = PathBuf::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let mut poll_fd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        unsafe { libc::poll(&mut poll_fd, 1, 50) };
+
+        {
+            let mut watches = watches.lock().unwrap_or_else(|e| e.into_inner());
+            if watches.stopped {
+                break;
+            }
+            if std::mem::take(&mut watches.dir_switched) {
+                dir_events = Debounce::default();
+                dir_changes.clear();
+            }
+            if std::mem::take(&mut watches.preview_switched) {
+                preview_events = Debounce::default();
+                preview_path = watches.preview_path.clone();
+            }
+            loop {
+                let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+                if n <= 0 {
+                    break;
+                }
+                let n = n as usize;
+                let mut offset = 0;
+                while offset + EVENT_SIZE <= n {
+                    let event: libc::inotify_event =
+                        unsafe { std::ptr::read_unaligned(buf.as_ptr().add(offset) as *const _) };
+                    //events of previously watched directories don't matter anymore
+                    if event.mask & libc::IN_IGNORED == 0 {
+                        let now = Instant::now();
+                        if event.wd == watches.dir {
+                            dir_events.event(now);
+                            let name_end = (offset + EVENT_SIZE + event.len as usize).min(n);
+                            let name = &buf[offset + EVENT_SIZE..name_end];
+                            let path = changed_path(&watches.dir_path, name);
+                            if !dir_changes.contains(&path) {
+                                dir_changes.push(path);
+                            }
+                        }
+                        if event.wd == watches.preview {
+                            preview_events.event(now);
+                        }
+                    }
+                    offset += EVENT_SIZE + event.len as usize;
+                }
+            }
+        }
+
+        let sent = (!dir_events.is_due()
+            || changed
+                .send(WorkerMessage::DirChanged(std::mem::take(&mut dir_changes)))
+                .is_ok())
+            && (!preview_events.is_due()
+                || changed
+                    .send(WorkerMessage::PreviewChanged(preview_path.clone()))
+                    .is_ok());
+        //the event loop is gone
+        if !sent {
+            break;
+        }
+    }
+    let mut watches = watches.lock().unwrap_or_else(|e| e.into_inner());
+    watches.fd = -1;
+    unsafe { libc::close(fd) };
+}
+
+/// the path an event is about, name is NUL padded and empty for the directory itself
+#[cfg(target_os = "linux")]
+fn changed_path(dir: &Path, name: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+    if name.is_empty() {
+        dir.to_path_buf()
+    } else {
+        dir.join(std::ffi::OsStr::from_bytes(name))
+    }
+}
@@ -1,18 +1,39 @@
-use crate::controller::{AppEvents, Controller};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::terminal::{self, EnterAlternateScreen, supports_keyboard_enhancement};
+use pathfinder_core::error::Error;
+use pathfinder_core::opener;
+use pathfinder_core::shell;
+use pathfinder_core::ui::controller::{AppEvents, Controller};
+use pathfinder_core::ui::windows::explorer_table::ChooseMode;
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// if the keyboard enhancement flags were pushed and have to be popped again
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+/// the terminal is only asked once, it doesn't change while the explorer runs
+static KEYBOARD_ENHANCEMENT_SUPPORTED: OnceLock<bool> = OnceLock::new();
 
 pub struct App {
     controller: Controller,
     exit: bool,
+    ///what was picked in the chooser mode
+    chosen: Option<Vec<PathBuf>>,
 }
 
 impl App {
-    pub fn new() -> App {
+    pub fn new(chooser: Option<ChooseMode>) -> App {
         App {
             exit: false,
-            controller: Controller::new(),
+            controller: Controller::new(chooser),
+            chosen: None,
         }
     }
 
@@ -25,10 +46,75 @@ impl App {
                 Ok(event) => match event {
                     AppEvents::None => {}
                     AppEvents::Exit => self.exit = true,
-                    _ => panic!(),
+                    AppEvents::RunInTerminal(command) => self.run_in_terminal(terminal, command)?,
+                    AppEvents::Choose(paths) => {
+                        self.chosen = Some(paths);
+                        self.exit = true;
+                    }
+                    //the controller handles everything else itself
+                    _ => {}
                 },
             }
         }
         Ok(())
     }
+
+    /// the paths picked in the chooser mode, None if it was quit without choosing
+    pub fn chosen(&self) -> Option<&[PathBuf]> {
+        self.chosen.as_deref()
+    }
+
+    /// the folder the explorer shows
+    pub fn current_dir(&self) -> &Path {
+        self.controller.file_manager.current_dir()
+    }
+
+    /// hands the terminal to command until it exits, the listing is read again afterwards
+    fn run_in_terminal(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mut command: Command,
+    ) -> io::Result<()> {
+        //the input thread would steal the keys of the program otherwise
+        self.controller.pause_input();
+        terminal.show_cursor()?;
+        disable_input_extras();
+        ratatui::restore();
+        let status = shell::run_attached(&mut command);
+        terminal::enable_raw_mode()?;
+        crossterm::execute!(io::stdout(), EnterAlternateScreen)?;
+        enable_input_extras();
+        self.controller.resume_input();
+        terminal.clear()?;
+        if let Err(e) = status {
+            self.controller.show_error(Error::Launch {
+                program: opener::program_name(&command),
+                source: e,
+            });
+        }
+        self.controller.file_manager.update();
+        Ok(())
+    }
+}
+
+/// turns on mouse capture and, if the terminal supports it, the reporting of combinations
+/// like Ctrl-Shift-p. The explorer is fully usable without them, so errors are ignored
+pub fn enable_input_extras() {
+    let _ = crossterm::execute!(io::stdout(), EnableMouseCapture);
+    let supported = KEYBOARD_ENHANCEMENT_SUPPORTED
+        .get_or_init(|| matches!(supports_keyboard_enhancement(), Ok(true)));
+    if *supported {
+        let _ = crossterm::execute!(
+            io::stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        );
+        KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+    }
+}
+
+pub fn disable_input_extras() {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+        let _ = crossterm::execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+    let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
 }
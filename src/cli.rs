@@ -0,0 +1,232 @@
+use crate::headless::{self, Subcommand};
+use pathfinder_core::shell;
+use pathfinder_core::ui::windows::explorer_table::ChooseMode;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::fd::OwnedFd;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+usage: pathfinder [options]
+       pathfinder <command> [args]
+
+options:
+  --cwd-file <path>          write the last folder to path on exit
+  --choose-files <out>       pick files, Enter writes them to out (- for stdout)
+                             and quits, destructive actions are disabled
+  --choose-dir <out>         pick a folder the same way
+  --print0                   separate the chosen paths with NUL, not newlines
+  --print-shell-init <shell> print a wrapper for bash, zsh or fish that
+                             changes into the last folder on exit
+  -h, --help                 show this help
+
+commands, run without the ui:
+  ls [-a] [--sort <mode>] [--json] [dir]   list a folder, e.g. --sort \"mtime desc\"
+  cp [-f] <source>... <folder>             copy into a folder, -f overwrites files
+                                           and merges folders
+  mv <source>... <target>                  rename or move into a folder
+  rm [-r] [--permanent] <path>...          move to the trash, -r for folders,
+                                           --permanent deletes instead
+  mkdir <path>...                          create folders with their parents
+  du [--depth <n>] [--json] [dir]          disk usage, sizes include everything below";
+
+/// What the arguments ask for
+#[derive(Debug, PartialEq)]
+pub enum Cli {
+    Explore(Options),
+    Headless(Subcommand),
+    PrintShellInit(InitShell),
+    Help,
+}
+
+/// The options of the explorer itself
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub cwd_file: Option<PathBuf>,
+    pub chooser: Option<Chooser>,
+}
+
+/// The explorer runs as a picker for another program
+#[derive(Debug, PartialEq)]
+pub struct Chooser {
+    pub mode: ChooseMode,
+    /// where the chosen paths are written, `-` is stdout
+    pub output: PathBuf,
+    pub null_separated: bool,
+}
+
+/// The shells --print-shell-init has a wrapper for
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InitShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl InitShell {
+    fn parse(name: &str) -> Result<InitShell, String> {
+        match name {
+            "bash" => Ok(InitShell::Bash),
+            "zsh" => Ok(InitShell::Zsh),
+            "fish" => Ok(InitShell::Fish),
+            _ => Err(format!("no shell init for {}, use bash, zsh or fish", name)),
+        }
+    }
+}
+
+/// parses the arguments without the program name
+pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Cli, String> {
+    let mut options = Options::default();
+    let mut null_separated = false;
+    let mut args = args.into_iter().peekable();
+    if let Some(name) = args.peek().and_then(|arg| arg.to_str())
+        && headless::SUBCOMMANDS.contains(&name)
+    {
+        let name = name.to_owned();
+        args.next();
+        return headless::parse(&name, args).map(Cli::Headless);
+    }
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.to_str() {
+            Some("-h") | Some("--help") => return Ok(Cli::Help),
+            Some("--cwd-file") => options.cwd_file = Some(PathBuf::from(value("--cwd-file")?)),
+            Some(name @ ("--choose-files" | "--choose-dir")) => {
+                if options.chooser.is_some() {
+                    return Err(
+                        "only one of --choose-files and --choose-dir can be used".to_owned()
+                    );
+                }
+                let mode = match name {
+                    "--choose-files" => ChooseMode::Files,
+                    _ => ChooseMode::Dir,
+                };
+                let output = PathBuf::from(value(name)?);
+                options.chooser = Some(Chooser {
+                    mode,
+                    output,
+                    null_separated: false,
+                });
+            }
+            Some("--print0") => null_separated = true,
+            Some("--print-shell-init") => {
+                let shell = value("--print-shell-init")?;
+                return InitShell::parse(&shell.to_string_lossy()).map(Cli::PrintShellInit);
+            }
+            _ => return Err(format!("unknown argument {}", arg.to_string_lossy())),
+        }
+    }
+    match &mut options.chooser {
+        Some(chooser) => chooser.null_separated = null_separated,
+        None if null_separated => {
+            return Err("--print0 needs --choose-files or --choose-dir".to_owned());
+        }
+        None => {}
+    }
+    Ok(Cli::Explore(options))
+}
+
+/// A function `pf` that starts program with --cwd-file and changes into the folder it wrote
+pub fn shell_init(shell: InitShell, program: &str) -> String {
+    //quoted, the name may contain spaces or quotes
+    let quote = |name: &str| shell::quote(Path::new(name)).to_string_lossy().into_owned();
+    match shell {
+        InitShell::Bash | InitShell::Zsh => {
+            let program = quote(program);
+            format!(
+                r#"pf() {{
+    local cwd_file cwd
+    cwd_file="$(mktemp -t pathfinder-cwd.XXXXXX)" || return
+    command {program} --cwd-file "$cwd_file" "$@"
+    cwd="$(cat -- "$cwd_file")"
+    rm -f -- "$cwd_file"
+    if [ -n "$cwd" ] && [ "$cwd" != "$PWD" ]; then
+        cd -- "$cwd"
+    fi
+}}
+"#
+            )
+        }
+        InitShell::Fish => {
+            //in single quotes fish reads \\ as one backslash
+            let program = quote(&program.replace('\\', "\\\\"));
+            format!(
+                r#"function pf
+    set -l cwd_file (mktemp -t pathfinder-cwd.XXXXXX); or return
+    command {program} --cwd-file $cwd_file $argv
+    set -l cwd (cat -- $cwd_file | string collect)
+    rm -f -- $cwd_file
+    if test -n "$cwd"; and test "$cwd" != "$PWD"
+        cd -- $cwd
+    end
+end
+"#
+            )
+        }
+    }
+}
+
+/// writes dir to path without a trailing newline, names that aren't utf-8 are kept as they are
+pub fn write_cwd_file(path: &Path, dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(dir.as_os_str()).to_vec();
+    #[cfg(not(unix))]
+    let bytes = dir.to_string_lossy().into_owned().into_bytes();
+    fs::write(path, bytes)
+}
+
+/// writes paths to the output of chooser, each one followed by a newline or NUL
+pub fn write_chosen(chooser: &Chooser, paths: &[PathBuf]) -> io::Result<()> {
+    let separator = if chooser.null_separated { b'\0' } else { b'\n' };
+    let mut bytes = Vec::new();
+    for path in paths {
+        #[cfg(unix)]
+        bytes.extend_from_slice(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()));
+        #[cfg(not(unix))]
+        bytes.extend_from_slice(path.to_string_lossy().as_bytes());
+        bytes.push(separator);
+    }
+    if chooser.output == Path::new("-") {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&bytes)?;
+        stdout.flush()
+    } else {
+        fs::write(&chooser.output, bytes)
+    }
+}
+
+/// Points stdout to the terminal, so the ui can be drawn while stdout is a pipe like in
+/// `files=$(pathfinder --choose-files -)`. Returns the old stdout for restore_stdout
+#[cfg(unix)]
+pub fn stdout_to_tty() -> io::Result<OwnedFd> {
+    use std::os::fd::{AsRawFd, FromRawFd};
+    let tty = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")?;
+    //SAFETY: dup and dup2 only work on file descriptors, the new one is owned by the OwnedFd
+    unsafe {
+        let saved = libc::dup(libc::STDOUT_FILENO);
+        if saved < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let saved = OwnedFd::from_raw_fd(saved);
+        if libc::dup2(tty.as_raw_fd(), libc::STDOUT_FILENO) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(saved)
+    }
+}
+
+#[cfg(unix)]
+pub fn restore_stdout(saved: OwnedFd) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+    io::stdout().flush()?;
+    //SAFETY: saved is an open file descriptor
+    if unsafe { libc::dup2(saved.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
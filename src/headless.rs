@@ -0,0 +1,361 @@
+use pathfinder_core::config::Config;
+use pathfinder_core::disk_usage::{self, DuNode};
+use pathfinder_core::error::{Context, Operation};
+use pathfinder_core::file_manager::FileManager;
+use pathfinder_core::sorting::Sorting;
+use pathfinder_core::util::{self, SizeFormat};
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub const SUBCOMMANDS: [&str; 6] = ["ls", "cp", "mv", "rm", "mkdir", "du"];
+
+/// A file operation of `pathfinder <command>`, run without the ui
+#[derive(Debug, PartialEq)]
+pub enum Subcommand {
+    Ls {
+        dir: PathBuf,
+        all: bool,
+        sorting: Option<Sorting>,
+        json: bool,
+    },
+    /// copies the sources into the folder dest
+    Cp {
+        sources: Vec<PathBuf>,
+        dest: PathBuf,
+        force: bool,
+    },
+    /// renames a single source to dest or moves the sources into the folder dest
+    Mv {
+        sources: Vec<PathBuf>,
+        dest: PathBuf,
+    },
+    /// moves the paths to the trash, or deletes them if permanent
+    Rm {
+        paths: Vec<PathBuf>,
+        recursive: bool,
+        permanent: bool,
+    },
+    Mkdir(Vec<PathBuf>),
+    Du {
+        dir: PathBuf,
+        depth: usize,
+        json: bool,
+    },
+}
+
+/// parses the arguments after the name of the subcommand
+pub fn parse(name: &str, args: impl IntoIterator<Item = OsString>) -> Result<Subcommand, String> {
+    let mut flags = Vec::new();
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            //everything after -- is a path, even if it starts with a dash
+            Some("--") => paths.extend(args.by_ref().map(PathBuf::from)),
+            Some("--sort") | Some("--depth") => {
+                let value = args
+                    .next()
+                    .ok_or(format!("{} needs a value", arg.display()))?;
+                flags.push((arg.to_string_lossy().into_owned(), Some(value)));
+            }
+            Some(flag) if flag.starts_with('-') && flag.len() > 1 => {
+                flags.push((flag.to_owned(), None));
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let subcommand = match name {
+        "ls" => Subcommand::Ls {
+            all: take_flag(&mut flags, &["-a", "--all"]),
+            json: take_flag(&mut flags, &["--json"]),
+            sorting: match take_value(&mut flags, "--sort") {
+                Some(sorting) => Some(Sorting::parse(&sorting.to_string_lossy())?),
+                None => None,
+            },
+            dir: single_dir(paths)?,
+        },
+        "cp" | "mv" => {
+            let force = name == "cp" && take_flag(&mut flags, &["-f", "--force"]);
+            let dest = paths.pop().ok_or("missing source and target")?;
+            if paths.is_empty() {
+                return Err(format!("missing target after {}", dest.display()));
+            }
+            match name {
+                "cp" => Subcommand::Cp {
+                    sources: paths,
+                    dest,
+                    force,
+                },
+                _ => Subcommand::Mv {
+                    sources: paths,
+                    dest,
+                },
+            }
+        }
+        "rm" if paths.is_empty() => return Err("missing paths".to_owned()),
+        "rm" => Subcommand::Rm {
+            recursive: take_flag(&mut flags, &["-r", "--recursive"]),
+            permanent: take_flag(&mut flags, &["--permanent"]),
+            paths,
+        },
+        "mkdir" if paths.is_empty() => return Err("missing paths".to_owned()),
+        "mkdir" => Subcommand::Mkdir(paths),
+        "du" => Subcommand::Du {
+            json: take_flag(&mut flags, &["--json"]),
+            depth: match take_value(&mut flags, "--depth") {
+                Some(depth) => depth
+                    .to_string_lossy()
+                    .parse()
+                    .map_err(|_e| format!("--depth needs a number, not {}", depth.display()))?,
+                None => 1,
+            },
+            dir: single_dir(paths)?,
+        },
+        _ => return Err(format!("unknown command {}", name)),
+    };
+    match flags.first() {
+        Some((flag, _)) => Err(format!("unknown option {}", flag)),
+        None => Ok(subcommand),
+    }
+}
+
+/// removes the flags called one of names, true if there were any
+fn take_flag(flags: &mut Vec<(String, Option<OsString>)>, names: &[&str]) -> bool {
+    let before = flags.len();
+    flags.retain(|(flag, _)| !names.contains(&flag.as_str()));
+    flags.len() != before
+}
+
+fn take_value(flags: &mut Vec<(String, Option<OsString>)>, name: &str) -> Option<OsString> {
+    let index = flags.iter().position(|(flag, _)| flag == name)?;
+    flags.remove(index).1
+}
+
+/// the folder of ls and du, `.` if none is given
+fn single_dir(mut paths: Vec<PathBuf>) -> Result<PathBuf, String> {
+    match paths.len() {
+        0 => Ok(PathBuf::from(".")),
+        1 => Ok(paths.remove(0)),
+        _ => Err("only one folder can be given".to_owned()),
+    }
+}
+
+/// An entry of `ls --json`
+#[derive(Serialize)]
+struct LsEntry {
+    name: String,
+    path: String,
+    #[serde(rename = "type")]
+    file_type: &'static str,
+    size: u64,
+    /// seconds since the unix epoch
+    modified: Option<u64>,
+    mode: u32,
+    symlink_target: Option<String>,
+}
+
+/// A node of `du --json`
+#[derive(Serialize)]
+struct DuEntry {
+    name: String,
+    size: u64,
+    is_dir: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<DuEntry>,
+}
+
+impl DuEntry {
+    fn new(node: &DuNode, depth: usize) -> DuEntry {
+        DuEntry {
+            name: node.name.to_string_lossy().into_owned(),
+            size: node.size,
+            is_dir: node.is_dir,
+            children: match depth {
+                0 => Vec::new(),
+                _ => node
+                    .children
+                    .iter()
+                    .map(|child| DuEntry::new(child, depth - 1))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Runs subcommand with the FileManager the ui uses, so sorting, hidden files and copying
+/// work the same. The output goes to out, errors name the operation and the path
+pub fn run(subcommand: Subcommand, config: &Config, out: &mut impl Write) -> Result<(), String> {
+    //the FileManager moves the process into the folders it reads
+    let cwd = std::env::current_dir().map_err(|e| format!("current folder: {}", e))?;
+    let result = run_in_file_manager(subcommand, config, out);
+    let _ = std::env::set_current_dir(cwd);
+    result
+}
+
+fn run_in_file_manager(
+    subcommand: Subcommand,
+    config: &Config,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut file_manager = FileManager::new(sender, config);
+    let write_error = |e: std::io::Error| format!("writing the output: {}", e);
+    match subcommand {
+        Subcommand::Ls {
+            dir,
+            all,
+            sorting,
+            json,
+        } => {
+            file_manager.show_hidden |= all;
+            file_manager.change_dir(dir).map_err(|e| e.to_string())?;
+            while file_manager.is_loading() {
+                let message = receiver
+                    .recv()
+                    .map_err(|_e| "reading the folder stopped".to_owned())?;
+                file_manager.handle_worker_message(message);
+            }
+            file_manager.sort_if_changed();
+            if let Some(sorting) = sorting {
+                file_manager.sort(sorting);
+            }
+            let entries = file_manager.get_entries();
+            if json {
+                let entries: Vec<LsEntry> = entries
+                    .iter()
+                    .map(|entry| LsEntry {
+                        name: entry.name.to_string_lossy().into_owned(),
+                        path: entry.path.to_string_lossy().into_owned(),
+                        file_type: entry.file_type.label(),
+                        size: entry.size,
+                        modified: entry
+                            .modified
+                            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                            .map(|duration| duration.as_secs()),
+                        mode: entry.mode,
+                        symlink_target: entry
+                            .symlink_target
+                            .as_ref()
+                            .map(|target| target.to_string_lossy().into_owned()),
+                    })
+                    .collect();
+                write_json(out, &entries)?;
+            } else {
+                for entry in entries {
+                    writeln!(out, "{}", entry.name.to_string_lossy()).map_err(write_error)?;
+                }
+            }
+        }
+        Subcommand::Cp {
+            sources,
+            dest,
+            force,
+        } => {
+            if !dest.is_dir() {
+                return Err(format!("{} is not a folder", dest.display()));
+            }
+            //relative sources have to be resolved before paste changes into dest
+            for source in sources {
+                source
+                    .symlink_metadata()
+                    .map_err(|e| format!("{}: {}", source.display(), e))?;
+                file_manager
+                    .add_to_selection(source)
+                    .map_err(|e| e.to_string())?;
+            }
+            file_manager
+                .change_dir(dest.clone())
+                .map_err(|e| e.to_string())?;
+            let conflicts = file_manager.paste_conflicts();
+            if conflicts > 0 && !force {
+                return Err(format!(
+                    "{} of the files already exist in {}, use --force to overwrite them",
+                    conflicts,
+                    dest.display()
+                ));
+            }
+            file_manager.paste().map_err(|e| e.to_string())?;
+        }
+        Subcommand::Mv { sources, dest } => {
+            let into_dir = dest.is_dir();
+            if !into_dir && sources.len() > 1 {
+                return Err(format!("{} is not a folder", dest.display()));
+            }
+            for source in sources {
+                let target = match source.file_name() {
+                    Some(name) if into_dir => dest.join(name),
+                    _ => dest.clone(),
+                };
+                file_manager
+                    .rename(&source, &target)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Subcommand::Rm {
+            paths,
+            recursive,
+            permanent,
+        } => {
+            for path in paths {
+                //like rm, folders are only deleted with everything in them if asked for
+                if !recursive && path.is_dir() && !path.is_symlink() {
+                    return Err(format!("{} is a folder, use -r", path.display()));
+                }
+                let result = match permanent {
+                    true => file_manager.delete(&path),
+                    false => file_manager.trash(&path),
+                };
+                result.map_err(|e| e.to_string())?;
+            }
+        }
+        Subcommand::Mkdir(paths) => {
+            for path in paths {
+                file_manager
+                    .create_folder(path)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Subcommand::Du { dir, depth, json } => {
+            //the scan skips what it can't read, so check first to report it
+            fs::read_dir(&dir)
+                .context(Operation::ReadDir, &dir)
+                .map_err(|e| e.to_string())?;
+            let root = disk_usage::scan(&dir);
+            if json {
+                let mut entry = DuEntry::new(&root, depth);
+                //the scan only knows the last component, which `.` doesn't have
+                entry.name = dir.to_string_lossy().into_owned();
+                write_json(out, &entry)?;
+            } else {
+                write_du(out, &root, &dir, depth).map_err(write_error)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<(), String> {
+    serde_json::to_writer_pretty(&mut *out, value)
+        .map_err(|e| e.to_string())
+        .and_then(|()| writeln!(out).map_err(|e| e.to_string()))
+        .map_err(|e| format!("writing the output: {}", e))
+}
+
+/// prints the tree below node down to depth like `du`, the biggest first and node itself last
+fn write_du(out: &mut impl Write, node: &DuNode, path: &Path, depth: usize) -> std::io::Result<()> {
+    if depth > 0 {
+        for child in &node.children {
+            write_du(out, child, &path.join(&child.name), depth - 1)?;
+        }
+    }
+    writeln!(
+        out,
+        "{:>10}  {}",
+        util::format_size(node.size, SizeFormat::Iec),
+        path.display()
+    )
+}
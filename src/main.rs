@@ -1,17 +1,88 @@
 mod app;
-mod controller;
-mod file_manager;
-mod message;
+mod cli;
+mod headless;
 mod test;
-mod util;
-mod windows;
 
 use crate::app::App;
+use crate::cli::Cli;
+use pathfinder_core::config::Config;
 use std::io;
+use std::path::Path;
 
 fn main() -> io::Result<()> {
+    let mut args = std::env::args_os();
+    //the wrapper of --print-shell-init calls the program under the name it was started with
+    let program = args
+        .next()
+        .and_then(|arg0| {
+            let name = Path::new(&arg0).file_name()?;
+            Some(name.to_string_lossy().into_owned())
+        })
+        .unwrap_or("pathfinder".to_owned());
+    let options = match cli::parse(args) {
+        Ok(Cli::Explore(options)) => options,
+        Ok(Cli::Headless(subcommand)) => {
+            //a broken config is an error here, there is no ui to show it later
+            let config = match Config::load() {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{}: {}", program, e);
+                    std::process::exit(1);
+                }
+            };
+            if let Err(e) = headless::run(subcommand, &config, &mut io::stdout().lock()) {
+                eprintln!("{}: {}", program, e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(Cli::PrintShellInit(shell)) => {
+            print!("{}", cli::shell_init(shell, &program));
+            return Ok(());
+        }
+        Ok(Cli::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("{}: {}\n\n{}", program, e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    //the chosen paths go to stdout, so the ui is drawn on the terminal directly
+    #[cfg(unix)]
+    let saved_stdout = match &options.chooser {
+        Some(chooser)
+            if chooser.output == Path::new("-") && !io::IsTerminal::is_terminal(&io::stdout()) =>
+        {
+            Some(cli::stdout_to_tty()?)
+        }
+        _ => None,
+    };
+
     let mut terminal = ratatui::init();
-    let res: io::Result<()> = App::new().run(&mut terminal);
+    app::enable_input_extras();
+    let mut app = App::new(options.chooser.as_ref().map(|chooser| chooser.mode));
+    let res: io::Result<()> = app.run(&mut terminal);
+    app::disable_input_extras();
     ratatui::restore();
-    res
+    //dropping the terminal shows the cursor, which must not end up in the chosen paths
+    drop(terminal);
+    #[cfg(unix)]
+    if let Some(saved_stdout) = saved_stdout {
+        cli::restore_stdout(saved_stdout)?;
+    }
+    res?;
+    if let Some(cwd_file) = &options.cwd_file {
+        cli::write_cwd_file(cwd_file, app.current_dir())?;
+    }
+    if let Some(chooser) = &options.chooser {
+        match app.chosen() {
+            Some(paths) => cli::write_chosen(chooser, paths)?,
+            //like other pickers, quitting without a choice is an error for the caller
+            None => std::process::exit(1),
+        }
+    }
+    Ok(())
 }
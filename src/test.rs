@@ -1,2 +1,162 @@
+/// creates a fresh, empty directory below the system temp dir
+#[cfg(test)]
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("pathfinder_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
 #[test]
-fn test_file_manager() {}
+fn test_cli() {
+    use crate::cli::{self, Chooser, Cli, InitShell, Options};
+    use pathfinder_core::ui::windows::explorer_table::ChooseMode;
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    let parse = |args: &[&str]| cli::parse(args.iter().map(OsString::from));
+    assert_eq!(parse(&[]), Ok(Cli::Explore(Options::default())));
+    assert_eq!(
+        parse(&["--cwd-file", "/tmp/cwd"]),
+        Ok(Cli::Explore(Options {
+            cwd_file: Some(PathBuf::from("/tmp/cwd")),
+            ..Default::default()
+        }))
+    );
+    assert_eq!(
+        parse(&["--print-shell-init", "fish"]),
+        Ok(Cli::PrintShellInit(InitShell::Fish))
+    );
+    match parse(&["--choose-dir", "-", "--print0"]) {
+        Ok(Cli::Explore(options)) => assert_eq!(
+            options.chooser,
+            Some(Chooser {
+                mode: ChooseMode::Dir,
+                output: PathBuf::from("-"),
+                null_separated: true,
+            })
+        ),
+        _ => panic!("--choose-dir wasn't parsed"),
+    }
+    assert!(parse(&["--print0"]).is_err());
+    assert!(parse(&["--choose-files", "a", "--choose-dir", "b"]).is_err());
+    assert!(parse(&["--cwd-file"]).is_err());
+    assert!(parse(&["--print-shell-init", "tcsh"]).is_err());
+    assert!(parse(&["--frobnicate"]).is_err());
+
+    for shell in [InitShell::Bash, InitShell::Zsh, InitShell::Fish] {
+        let init = cli::shell_init(shell, "pathfinder");
+        assert!(init.contains("command 'pathfinder' --cwd-file"));
+    }
+    let init = cli::shell_init(InitShell::Bash, "it's pf");
+    assert!(init.contains("command 'it'\\''s pf' --cwd-file"));
+
+    let dir = temp_dir("cli");
+    let cwd_file = dir.join("cwd");
+    cli::write_cwd_file(&cwd_file, &dir.join("a b")).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&cwd_file).unwrap(),
+        dir.join("a b").to_string_lossy()
+    );
+
+    let chooser = Chooser {
+        mode: ChooseMode::Files,
+        output: dir.join("chosen"),
+        null_separated: true,
+    };
+    cli::write_chosen(&chooser, &[dir.join("a"), dir.join("b")]).unwrap();
+    let expected = format!("{}\0{}\0", dir.join("a").display(), dir.join("b").display());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("chosen")).unwrap(),
+        expected
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// the commands work in the working directory of the whole process,
+/// so tests that change it must not run in parallel
+#[cfg(test)]
+static CWD_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[test]
+fn test_headless() {
+    use crate::cli::{self, Cli};
+    use crate::headless::{self, Subcommand};
+    use pathfinder_core::config::Config;
+    use pathfinder_core::sorting::{SortKey, Sorting};
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    let _lock = CWD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let parse = |args: &[&str]| cli::parse(args.iter().map(OsString::from));
+    assert_eq!(
+        parse(&["ls", "-a", "--sort", "size desc", "--json", "src"]),
+        Ok(Cli::Headless(Subcommand::Ls {
+            dir: PathBuf::from("src"),
+            all: true,
+            sorting: Some(Sorting::Descending(SortKey::Size)),
+            json: true,
+        }))
+    );
+    assert_eq!(
+        parse(&["rm", "-r", "--", "-x"]),
+        Ok(Cli::Headless(Subcommand::Rm {
+            paths: vec![PathBuf::from("-x")],
+            recursive: true,
+            permanent: false,
+        }))
+    );
+    assert!(parse(&["cp", "a"]).is_err());
+    assert!(parse(&["mkdir", "-p", "a"]).is_err());
+    assert!(parse(&["du", "--depth", "deep"]).is_err());
+
+    let dir = temp_dir("headless");
+    std::fs::create_dir(dir.join("src")).unwrap();
+    std::fs::write(dir.join("src/big"), "0123456789").unwrap();
+    std::fs::write(dir.join("small"), "0").unwrap();
+    std::fs::write(dir.join(".hidden"), "").unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    let config = Config::default();
+    let run = |args: &[&str]| {
+        let mut out = Vec::new();
+        let subcommand = match cli::parse(args.iter().map(OsString::from)) {
+            Ok(Cli::Headless(subcommand)) => subcommand,
+            _ => panic!("{:?} isn't a command", args),
+        };
+        headless::run(subcommand, &config, &mut out).map(|()| String::from_utf8(out).unwrap())
+    };
+
+    assert_eq!(
+        run(&["ls", "--sort", "name"]),
+        Ok("small\nsrc\n".to_owned())
+    );
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["ls", "-a", "--json", "--sort", "size desc"]).unwrap())
+            .unwrap();
+    assert_eq!(json[0]["name"], "small");
+    assert_eq!(json.as_array().unwrap().len(), 3);
+
+    run(&["mkdir", "out/deep"]).unwrap();
+    run(&["cp", "small", "src", "out"]).unwrap();
+    assert!(dir.join("out/src/big").exists());
+    assert!(run(&["cp", "small", "out"]).is_err());
+    run(&["cp", "-f", "small", "out"]).unwrap();
+    //folders that exist already are merged
+    std::fs::write(dir.join("src/new"), "").unwrap();
+    run(&["cp", "-f", "src", "out"]).unwrap();
+    assert!(dir.join("out/src/new").exists() && dir.join("out/src/big").exists());
+    run(&["mv", "out/small", "out/moved"]).unwrap();
+    assert!(dir.join("out/moved").exists());
+    assert!(run(&["rm", "out"]).is_err());
+    //the test doesn't fill the trash of the user
+    run(&["rm", "-r", "--permanent", "out"]).unwrap();
+    assert!(!dir.join("out").exists());
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["du", "--json"]).unwrap()).unwrap();
+    assert_eq!(json["size"], 11);
+    assert_eq!(json["children"][0]["name"], "src");
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,11 +0,0 @@
-use ratatui::layout::{Constraint, Flex, Rect};
-
-pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
-    let vertical =
-        ratatui::layout::Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
-    let horizontal =
-        ratatui::layout::Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
-    let [area] = vertical.areas(area);
-    let [area] = horizontal.areas(area);
-    area
-}
@@ -1,5 +0,0 @@
-pub mod confirmation_popup;
-pub mod key_mapping_popup;
-pub mod new_file_popup;
-pub mod sorting_popup;
-pub mod text_field_popup;